@@ -0,0 +1,377 @@
+use core::fmt::Error;
+use core::fmt::Formatter;
+use serde::Deserialize;
+use serenity::model::id::UserId;
+use serenity::prelude::TypeMapKey;
+use std::collections::HashSet;
+use std::fmt::Display;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tracing_subscriber::EnvFilter;
+
+const DEFAULT_CONFIG_PATH: &str = "logistic_robot.toml";
+
+// Everything the bot needs to start. Values come from, in increasing order of
+// priority: the defaults, the TOML config file, LOGISTIC_ROBOT_* environment
+// variables and command line arguments.
+#[derive(Clone, Debug)]
+pub struct Config {
+    pub token: String,
+    pub prefix: String,
+    // None uses the materials.json built into the binary.
+    pub materials_path: Option<PathBuf>,
+    pub settings_storage: String,
+    pub settings_path: PathBuf,
+    pub log_level: String,
+    pub owners: HashSet<UserId>,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ConfigFile {
+    token: Option<String>,
+    prefix: Option<String>,
+    materials_path: Option<PathBuf>,
+    settings_storage: Option<String>,
+    settings_path: Option<PathBuf>,
+    log_level: Option<String>,
+    owners: Option<Vec<u64>>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ConfigProblem {
+    UnreadableFile { path: PathBuf, why: String },
+    InvalidToml { path: PathBuf, why: String },
+    UnknownArgument(String),
+    MissingArgumentValue(String),
+    MissingToken,
+    InvalidPrefix(String),
+    UnknownStorage(String),
+    InvalidLogLevel(String),
+    InvalidOwnerId(String),
+    MissingMaterialsFile(PathBuf),
+}
+impl Display for ConfigProblem {
+    fn fmt(&self, formatter: &mut Formatter) -> Result<(), Error> {
+        match self {
+            ConfigProblem::UnreadableFile { path, why } => {
+                write!(formatter, "Unable to read config file {}: {}", path.display(), why)
+            }
+            ConfigProblem::InvalidToml { path, why } => {
+                write!(formatter, "Invalid config file {}: {}", path.display(), why)
+            }
+            ConfigProblem::UnknownArgument(argument) => write!(
+                formatter,
+                "Unknown argument {}, expected one of --config, --token, --prefix, --materials, --settings-storage, --settings-path, --log-level or --owner",
+                argument
+            ),
+            ConfigProblem::MissingArgumentValue(argument) => {
+                write!(formatter, "No value found for {}", argument)
+            }
+            ConfigProblem::MissingToken => write!(
+                formatter,
+                "No bot token set. Use token in the config file, LOGISTIC_ROBOT_TOKEN or --token"
+            ),
+            ConfigProblem::InvalidPrefix(prefix) => write!(
+                formatter,
+                "Invalid command prefix \"{}\", it must not be empty or contain spaces",
+                prefix
+            ),
+            ConfigProblem::UnknownStorage(storage) => write!(
+                formatter,
+                "Unknown settings storage {}, expected json or sqlite",
+                storage
+            ),
+            ConfigProblem::InvalidLogLevel(log_level) => write!(
+                formatter,
+                "Invalid log level {}, expected e.g. error, warn, info, debug or trace",
+                log_level
+            ),
+            ConfigProblem::InvalidOwnerId(owner) => {
+                write!(formatter, "Invalid owner id {}, expected a Discord user id", owner)
+            }
+            ConfigProblem::MissingMaterialsFile(path) => {
+                write!(formatter, "Materials file {} does not exist", path.display())
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ConfigError {
+    pub problems: Vec<ConfigProblem>,
+}
+impl Display for ConfigError {
+    fn fmt(&self, formatter: &mut Formatter) -> Result<(), Error> {
+        let problems: Vec<String> = self.problems.iter().map(|problem| problem.to_string()).collect();
+        write!(formatter, "{}", problems.join("\n"))
+    }
+}
+
+impl Config {
+    pub fn load() -> Result<Config, ConfigError> {
+        Config::load_from(std::env::args().skip(1).collect(), |name| std::env::var(name).ok())
+    }
+
+    fn load_from(
+        arguments: Vec<String>,
+        env: impl Fn(&str) -> Option<String>,
+    ) -> Result<Config, ConfigError> {
+        let mut problems: Vec<ConfigProblem> = Vec::new();
+        let arguments = parse_arguments(arguments, &mut problems);
+        let argument = |name: &str| {
+            arguments
+                .iter()
+                .rev()
+                .find(|(argument_name, _value)| argument_name == name)
+                .map(|(_argument_name, value)| value.clone())
+        };
+
+        // A missing config file is only a problem when one was asked for.
+        let explicit_config_path = argument("--config").or_else(|| env("LOGISTIC_ROBOT_CONFIG"));
+        let config_path = PathBuf::from(
+            explicit_config_path
+                .clone()
+                .unwrap_or_else(|| DEFAULT_CONFIG_PATH.to_string()),
+        );
+        let file = match std::fs::read_to_string(&config_path) {
+            Ok(contents) => toml::from_str(&contents).unwrap_or_else(|why| {
+                problems.push(ConfigProblem::InvalidToml {
+                    path: config_path.clone(),
+                    why: why.to_string(),
+                });
+                ConfigFile::default()
+            }),
+            Err(why) if explicit_config_path.is_some() || why.kind() != std::io::ErrorKind::NotFound => {
+                problems.push(ConfigProblem::UnreadableFile {
+                    path: config_path.clone(),
+                    why: why.to_string(),
+                });
+                ConfigFile::default()
+            }
+            Err(_) => ConfigFile::default(),
+        };
+        // Paths in the config file are relative to the file itself, and those
+        // from the environment, arguments or defaults to the working directory.
+        let config_directory = config_path.parent().map(Path::to_path_buf).unwrap_or_default();
+        let from_config_file = |path: PathBuf| config_directory.join(path);
+
+        let token = argument("--token")
+            .or_else(|| env("LOGISTIC_ROBOT_TOKEN"))
+            .or_else(|| env("TOKEN"))
+            .or(file.token)
+            .unwrap_or_default();
+        if token.trim().is_empty() {
+            problems.push(ConfigProblem::MissingToken);
+        }
+
+        let prefix = argument("--prefix")
+            .or_else(|| env("LOGISTIC_ROBOT_PREFIX"))
+            .or(file.prefix)
+            .unwrap_or_else(|| "!".to_string());
+        if prefix.is_empty() || prefix.chars().any(char::is_whitespace) {
+            problems.push(ConfigProblem::InvalidPrefix(prefix.clone()));
+        }
+
+        let materials_path = argument("--materials")
+            .or_else(|| env("LOGISTIC_ROBOT_MATERIALS_PATH"))
+            .map(PathBuf::from)
+            .or(file.materials_path.map(&from_config_file));
+        if let Some(materials_path) = &materials_path {
+            if !Path::new(materials_path).is_file() {
+                problems.push(ConfigProblem::MissingMaterialsFile(materials_path.clone()));
+            }
+        }
+
+        let settings_storage = argument("--settings-storage")
+            .or_else(|| env("LOGISTIC_ROBOT_SETTINGS_STORAGE"))
+            .or(file.settings_storage)
+            .unwrap_or_else(|| "json".to_string());
+        if settings_storage != "json" && settings_storage != "sqlite" {
+            problems.push(ConfigProblem::UnknownStorage(settings_storage.clone()));
+        }
+        let settings_path = argument("--settings-path")
+            .or_else(|| env("LOGISTIC_ROBOT_SETTINGS_PATH"))
+            .map(PathBuf::from)
+            .or(file.settings_path.map(&from_config_file))
+            .unwrap_or_else(|| PathBuf::from(format!("user_settings.{}", settings_storage)));
+
+        let log_level = argument("--log-level")
+            .or_else(|| env("LOGISTIC_ROBOT_LOG_LEVEL"))
+            .or(file.log_level)
+            .unwrap_or_else(|| "info".to_string());
+        if EnvFilter::try_new(&log_level).is_err() {
+            problems.push(ConfigProblem::InvalidLogLevel(log_level.clone()));
+        }
+
+        // Owners given on the command line or in the environment replace those
+        // in the config file rather than adding to them.
+        let owner_arguments: Vec<String> = arguments
+            .iter()
+            .filter(|(argument_name, _value)| argument_name == "--owner")
+            .map(|(_argument_name, value)| value.clone())
+            .collect();
+        let owner_ids: Vec<String> = match (owner_arguments.is_empty(), env("LOGISTIC_ROBOT_OWNERS")) {
+            (false, _) => owner_arguments,
+            (true, Some(owners)) => owners
+                .split(',')
+                .map(|owner| owner.trim().to_string())
+                .filter(|owner| !owner.is_empty())
+                .collect(),
+            (true, None) => file
+                .owners
+                .unwrap_or_default()
+                .iter()
+                .map(|owner| owner.to_string())
+                .collect(),
+        };
+        let mut owners: HashSet<UserId> = HashSet::new();
+        for owner in owner_ids {
+            match owner.parse::<u64>() {
+                Ok(owner_id) if owner_id > 0 => {
+                    owners.insert(UserId(owner_id));
+                }
+                _ => problems.push(ConfigProblem::InvalidOwnerId(owner)),
+            }
+        }
+
+        if !problems.is_empty() {
+            return Err(ConfigError { problems });
+        }
+        Ok(Config {
+            token,
+            prefix,
+            materials_path,
+            settings_storage,
+            settings_path,
+            log_level,
+            owners,
+        })
+    }
+}
+
+// Accepts both "--name value" and "--name=value".
+fn parse_arguments(arguments: Vec<String>, problems: &mut Vec<ConfigProblem>) -> Vec<(String, String)> {
+    let mut parsed: Vec<(String, String)> = Vec::new();
+    let mut argument_iter = arguments.into_iter();
+    while let Some(argument) = argument_iter.next() {
+        let (name, inline_value) = match argument.split_once('=') {
+            Some((name, value)) => (name.to_string(), Some(value.to_string())),
+            None => (argument.clone(), None),
+        };
+        match name.as_str() {
+            "--config" | "--token" | "--prefix" | "--materials" | "--settings-storage"
+            | "--settings-path" | "--log-level" | "--owner" => {
+                match inline_value.or_else(|| argument_iter.next()) {
+                    Some(value) => parsed.push((name, value)),
+                    None => problems.push(ConfigProblem::MissingArgumentValue(name)),
+                }
+            }
+            _ => problems.push(ConfigProblem::UnknownArgument(argument)),
+        }
+    }
+    parsed
+}
+
+impl TypeMapKey for Config {
+    type Value = Arc<Config>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn write_config_file(name: &str, contents: &str) -> PathBuf {
+        let directory = std::env::temp_dir().join(format!("logistic_robot_{}_{}", std::process::id(), name));
+        std::fs::create_dir_all(&directory).unwrap();
+        let path = directory.join("logistic_robot.toml");
+        std::fs::write(&path, contents).unwrap();
+        path
+    }
+
+    fn load(arguments: &[&str], env: &[(&str, &str)]) -> Result<Config, ConfigError> {
+        let env: HashMap<String, String> = env
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect();
+        Config::load_from(
+            arguments.iter().map(|argument| argument.to_string()).collect(),
+            move |name| env.get(name).cloned(),
+        )
+    }
+
+    #[test]
+    fn prefers_arguments_then_environment_then_file() {
+        let config_path = write_config_file(
+            "precedence",
+            "token = \"file\"\nprefix = \"?\"\nlog_level = \"warn\"\nsettings_path = \"settings.json\"\nowners = [1]\n",
+        );
+        let config_argument = format!("--config={}", config_path.display());
+        let config = load(
+            &[&config_argument, "--prefix", "%", "--owner", "3"],
+            &[
+                ("LOGISTIC_ROBOT_TOKEN", "env"),
+                ("LOGISTIC_ROBOT_PREFIX", "$"),
+                ("LOGISTIC_ROBOT_OWNERS", "2"),
+            ],
+        )
+        .unwrap();
+        assert_eq!(config.token, "env");
+        assert_eq!(config.prefix, "%");
+        assert_eq!(config.log_level, "warn");
+        assert_eq!(config.owners, [UserId(3)].iter().copied().collect());
+        assert_eq!(config.settings_path, config_path.parent().unwrap().join("settings.json"));
+
+        let config = load(&[&config_argument, "--settings-path", "other.json"], &[]).unwrap();
+        assert_eq!(config.token, "file");
+        assert_eq!(config.prefix, "?");
+        assert_eq!(config.owners, [UserId(1)].iter().copied().collect());
+        assert_eq!(config.settings_path, PathBuf::from("other.json"));
+        std::fs::remove_dir_all(config_path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn defaults_the_settings_path_to_the_storage_kind() {
+        let config_path = write_config_file("defaults", "token = \"file\"\nsettings_storage = \"sqlite\"\n");
+        let config = load(&["--config", config_path.to_str().unwrap()], &[]).unwrap();
+        assert_eq!(config.prefix, "!");
+        assert_eq!(config.settings_path, PathBuf::from("user_settings.sqlite"));
+        std::fs::remove_dir_all(config_path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn collects_every_problem() {
+        let missing_path = std::env::temp_dir().join("logistic_robot_missing_config.toml");
+        let error = load(
+            &[
+                "--config",
+                missing_path.to_str().unwrap(),
+                "--bogus",
+                "--prefix=a b",
+                "--owner",
+                "someone",
+                "--settings-storage",
+                "yaml",
+                "--log-level",
+            ],
+            &[],
+        )
+        .unwrap_err();
+        assert_eq!(
+            error.problems,
+            vec![
+                ConfigProblem::UnknownArgument("--bogus".to_string()),
+                ConfigProblem::MissingArgumentValue("--log-level".to_string()),
+                ConfigProblem::UnreadableFile {
+                    path: missing_path.clone(),
+                    why: std::fs::read_to_string(&missing_path).unwrap_err().to_string(),
+                },
+                ConfigProblem::MissingToken,
+                ConfigProblem::InvalidPrefix("a b".to_string()),
+                ConfigProblem::UnknownStorage("yaml".to_string()),
+                ConfigProblem::InvalidOwnerId("someone".to_string()),
+            ]
+        );
+    }
+}
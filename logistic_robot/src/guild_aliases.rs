@@ -0,0 +1,113 @@
+use crate::storage::{StorageError, UserSettingsStorage};
+use serde_json::Value;
+use serenity::model::id::GuildId;
+use serenity::prelude::*;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tracing::error;
+
+pub const ALIASES_KEY_PREFIX: &str = "aliases:";
+
+// Nicknames a server has added on top of the ones shipped in materials.json,
+// keyed by the lowercase alias and pointing at the canonical item name. Each
+// server's aliases are saved under "aliases:<guild id>" in the settings
+// storage.
+pub struct GuildAliasDatabase {
+    aliases_hash_map: HashMap<GuildId, HashMap<String, String>>,
+    storage: Arc<dyn UserSettingsStorage>
+}
+impl TypeMapKey for GuildAliasDatabase {
+    type Value = GuildAliasDatabase;
+}
+impl GuildAliasDatabase {
+    pub fn load(storage: Arc<dyn UserSettingsStorage>) -> Result<Self, StorageError> {
+        let mut aliases_hash_map = HashMap::new();
+        for (key, stored_aliases) in storage.load()? {
+            let guild_id = match key.strip_prefix(ALIASES_KEY_PREFIX).map(str::parse::<u64>) {
+                Some(Ok(guild_id)) => GuildId(guild_id),
+                _ => continue,
+            };
+            let aliases: HashMap<String, String> = stored_aliases
+                .into_iter()
+                .filter_map(|(alias, material_name)| match material_name {
+                    Value::String(material_name) => Some((alias, material_name)),
+                    _ => None,
+                })
+                .collect();
+            aliases_hash_map.insert(guild_id, aliases);
+        }
+        Ok(GuildAliasDatabase { aliases_hash_map, storage })
+    }
+
+    pub fn add(&mut self, guild_id: GuildId, alias: &str, material_name: &str) {
+        self.aliases_hash_map
+            .entry(guild_id)
+            .or_default()
+            .insert(alias.to_lowercase(), material_name.to_string());
+        self.save(guild_id);
+    }
+
+    pub fn remove(&mut self, guild_id: GuildId, alias: &str) -> Option<String> {
+        let removed = self
+            .aliases_hash_map
+            .get_mut(&guild_id)
+            .and_then(|aliases| aliases.remove(&alias.to_lowercase()));
+        if removed.is_some() {
+            self.save(guild_id);
+        }
+        removed
+    }
+
+    // Messages outside a server only get the shipped aliases.
+    pub fn get(&self, guild_id: Option<GuildId>) -> HashMap<String, String> {
+        guild_id
+            .and_then(|guild_id| self.aliases_hash_map.get(&guild_id))
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    fn save(&mut self, guild_id: GuildId) {
+        let key = format!("{}{}", ALIASES_KEY_PREFIX, guild_id);
+        let saved = match self.aliases_hash_map.get(&guild_id) {
+            Some(aliases) if !aliases.is_empty() => self.storage.save(
+                &key,
+                &aliases
+                    .iter()
+                    .map(|(alias, material_name)| (alias.clone(), Value::from(material_name.clone())))
+                    .collect(),
+            ),
+            _ => {
+                self.aliases_hash_map.remove(&guild_id);
+                self.storage.remove(&key)
+            }
+        };
+        if let Err(why) = saved {
+            error!("Unable to save aliases for {}: {}", guild_id, why);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::open_storage;
+
+    #[test]
+    fn keeps_aliases_across_restarts() {
+        let path = std::env::temp_dir().join(format!("logistic_robot_{}_aliases.json", std::process::id()));
+        let mut guild_alias_database = GuildAliasDatabase::load(open_storage("json", &path).unwrap()).unwrap();
+        guild_alias_database.add(GuildId(1), "Green Circuit", "Electronic Circuit");
+        guild_alias_database.add(GuildId(1), "gears", "Iron Gear Wheel");
+        guild_alias_database.add(GuildId(2), "red circuit", "Advanced Circuit");
+        guild_alias_database.remove(GuildId(1), "gears");
+        guild_alias_database.remove(GuildId(2), "red circuit");
+
+        let reloaded = GuildAliasDatabase::load(open_storage("json", &path).unwrap()).unwrap();
+        let mut expected = HashMap::new();
+        expected.insert("green circuit".to_string(), "Electronic Circuit".to_string());
+        assert_eq!(reloaded.get(Some(GuildId(1))), expected);
+        assert!(reloaded.get(Some(GuildId(2))).is_empty());
+        assert_eq!(reloaded.storage.load().unwrap().len(), 1);
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,92 @@
+use serenity::framework::standard::CommandResult;
+use serenity::framework::standard::macros::{command};
+use crate::guild_aliases::GuildAliasDatabase;
+use crate::handlers::flags::tokenize;
+use crate::material_database_key::MaterialDatabaseKey;
+use serenity::{model::channel::Message, prelude::*};
+use tracing::error;
+
+#[command]
+#[aliases("add-alias")]
+#[only_in(guilds)]
+#[required_permissions(MANAGE_GUILD)]
+pub async fn add_alias(context: &Context, message: &Message) -> CommandResult {
+    let tokens: Vec<String> = tokenize(&message.content);
+    let mut data = context.data.write().await;
+    let material_database = data.get::<MaterialDatabaseKey>().unwrap().clone();
+    let guild_alias_database = data.get_mut::<GuildAliasDatabase>().unwrap();
+    let guild_id = message.guild_id.unwrap();
+    let guild_aliases = guild_alias_database.get(Some(guild_id));
+    let response = match tokens.as_slice() {
+        [_command, alias, item_name] => {
+            if let Some(existing) = material_database.resolve_material(alias, &guild_aliases) {
+                format!(
+                    "Error: *{}* already refers to *{}*. Remove it first with !remove-alias.",
+                    alias, existing.name
+                )
+            } else {
+                match material_database.lookup_material(item_name, &guild_aliases) {
+                    Ok(material) => {
+                        guild_alias_database.add(guild_id, alias, &material.name);
+                        format!("*{}* now refers to *{}*.", alias, material.name)
+                    }
+                    Err(why) => format!("Error: {}", why),
+                }
+            }
+        }
+        _ => "Invalid syntax. Example: *!add-alias \"green circuit\" \"electronic circuit\"*".to_string(),
+    };
+
+    if let Err(why) = message.channel_id.say(&context.http, response).await {
+        error!("Unable to send message: {}", why);
+    }
+    Ok(())
+}
+
+#[command]
+#[aliases("remove-alias")]
+#[only_in(guilds)]
+#[required_permissions(MANAGE_GUILD)]
+pub async fn remove_alias(context: &Context, message: &Message) -> CommandResult {
+    let tokens: Vec<String> = tokenize(&message.content);
+    let mut data = context.data.write().await;
+    let guild_alias_database = data.get_mut::<GuildAliasDatabase>().unwrap();
+    let guild_id = message.guild_id.unwrap();
+    let response = match tokens.as_slice() {
+        [_command, alias] => match guild_alias_database.remove(guild_id, alias) {
+            Some(material_name) => format!("*{}* no longer refers to *{}*.", alias, material_name),
+            None => format!("Error: *{}* is not an alias in this server.", alias),
+        },
+        _ => "Invalid syntax. Example: *!remove-alias \"green circuit\"*".to_string(),
+    };
+
+    if let Err(why) = message.channel_id.say(&context.http, response).await {
+        error!("Unable to send message: {}", why);
+    }
+    Ok(())
+}
+
+#[command]
+#[only_in(guilds)]
+pub async fn aliases(context: &Context, message: &Message) -> CommandResult {
+    let data = context.data.read().await;
+    let guild_aliases = data.get::<GuildAliasDatabase>().unwrap().get(message.guild_id);
+    let mut lines: Vec<String> = guild_aliases
+        .into_iter()
+        .map(|(alias, material_name)| format!("{} → {}", alias, material_name))
+        .collect();
+    lines.sort();
+    let sent_message = message.channel_id.send_message(&context.http, |m| {
+        m.embed(|e| {
+            e.title("Aliases in this server:").description(match lines.is_empty() {
+                true => "None yet. Admins can add some with *!add-alias*.".to_string(),
+                false => lines.join("\n"),
+            })
+        })
+    });
+
+    if let Err(why) = sent_message.await {
+        error!("Unable to send message: {}", why);
+    }
+    Ok(())
+}
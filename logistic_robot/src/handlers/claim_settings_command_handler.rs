@@ -0,0 +1,60 @@
+use serenity::framework::standard::CommandResult;
+use serenity::framework::standard::macros::{command};
+use crate::config::Config;
+use crate::handlers::flags::tokenize;
+use crate::user_settings::UserSettingsDatabase;
+use serenity::model::id::UserId;
+use serenity::{model::channel::Message, prelude::*};
+use tracing::error;
+
+// Moves settings saved under a user name, from before settings were keyed by
+// id, to the author's account. Names aren't unique, so this only works when
+// exactly one saved name matches; otherwise a bot owner moves them by name.
+#[command]
+#[aliases("claim-settings")]
+pub async fn claim_settings(context: &Context, message: &Message) -> CommandResult {
+    let tokens: Vec<String> = tokenize(&message.content);
+    let mut data = context.data.write().await;
+    let owner = data.get::<Config>().unwrap().owners.contains(&message.author.id);
+    let user_settings_database = data.get_mut::<UserSettingsDatabase>().unwrap();
+    let response = match tokens.as_slice() {
+        [_command] => {
+            let migrated = user_settings_database
+                .legacy_settings_name(&message.author.name)
+                .and_then(|legacy_name| {
+                    user_settings_database.migrate_legacy_settings(&legacy_name, message.author.id)
+                });
+            match migrated {
+                Ok(()) => "Your old settings now belong to your account.".to_string(),
+                Err(why) => format!("Error: {}", why),
+            }
+        }
+        [_command, legacy_name, user_id] if owner => match parse_user_id(user_id) {
+            Some(user_id) => match user_settings_database.migrate_legacy_settings(legacy_name, user_id) {
+                Ok(()) => format!("Settings saved as *{}* now belong to <@{}>.", legacy_name, user_id),
+                Err(why) => format!("Error: {}", why),
+            },
+            None => format!("Error: Invalid user *{}*", user_id),
+        },
+        [_command, _legacy_name, _user_id] => {
+            "Only the bot's owners can move someone else's settings.".to_string()
+        }
+        _ => "Invalid syntax. Example: *!claim-settings*".to_string(),
+    };
+
+    if let Err(why) = message.channel_id.say(&context.http, response).await {
+        error!("Unable to send message: {}", why);
+    }
+    Ok(())
+}
+
+// Takes a plain id or a mention like <@123> or <@!123>.
+fn parse_user_id(user: &str) -> Option<UserId> {
+    user.trim_start_matches("<@")
+        .trim_start_matches('!')
+        .trim_end_matches('>')
+        .parse::<u64>()
+        .ok()
+        .filter(|user_id| *user_id > 0)
+        .map(UserId)
+}
@@ -0,0 +1,57 @@
+use serenity::framework::standard::CommandResult;
+use serenity::framework::standard::macros::{command};
+use crate::handlers::update_settings_command_handler::update_settings_at_level;
+use crate::handlers::user_settings_command_handler::add_settings_fields;
+use crate::user_settings::{SettingsLevel, UserSettingsDatabase};
+use serenity::{model::channel::Message, prelude::*};
+use tracing::error;
+
+#[command]
+#[aliases("server-settings")]
+#[only_in(guilds)]
+#[required_permissions(MANAGE_GUILD)]
+pub async fn server_settings(context: &Context, message: &Message) -> CommandResult {
+    show_or_update_defaults(context, message, SettingsLevel::Server).await
+}
+
+#[command]
+#[aliases("channel-settings")]
+#[only_in(guilds)]
+#[required_permissions(MANAGE_CHANNELS)]
+pub async fn channel_settings(context: &Context, message: &Message) -> CommandResult {
+    show_or_update_defaults(context, message, SettingsLevel::Channel).await
+}
+
+// Without flags, shows what someone who hasn't changed anything would get at
+// this level. With flags, they work like !update-settings.
+async fn show_or_update_defaults(
+    context: &Context,
+    message: &Message,
+    level: SettingsLevel,
+) -> CommandResult {
+    let name = match level {
+        SettingsLevel::Server => "server",
+        _ => "channel",
+    };
+    if message.content.split_ascii_whitespace().count() > 1 {
+        return update_settings_at_level(
+            context,
+            message,
+            level,
+            &format!("Default settings for this {} updated successfully.", name),
+        )
+        .await;
+    }
+    let data = context.data.read().await;
+    let user_settings_database = data.get::<UserSettingsDatabase>().unwrap();
+    let resolved_settings = user_settings_database.resolve_up_to(message, level);
+    let sent_message = message.channel_id.send_message(&context.http, |m| {
+        m.content(format!("Default settings for this {}:", name))
+            .embed(|e| add_settings_fields(e, &resolved_settings))
+    });
+
+    if let Err(why) = sent_message.await {
+        error!("Error sending message: {:?}", why);
+    }
+    Ok(())
+}
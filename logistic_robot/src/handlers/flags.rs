@@ -14,10 +14,7 @@ impl<'a> Flags<'a> {
     }
 
     pub fn get(&'a self, flag_name: &'a str) -> Option<Option<&'a str>> {
-        match self.flags.get(flag_name) {
-            Some(&flag_value) => Some(flag_value),
-            None => None,
-        }
+        self.flags.get(flag_name).copied()
     }
 
     pub fn get_all(&'a self) -> Vec<(&'a str, Option<&'a str>)> {
@@ -28,14 +25,38 @@ impl<'a> Flags<'a> {
     }
 }
 
+pub fn tokenize(content: &str) -> Vec<String> {
+    let mut tokens: Vec<String> = Vec::new();
+    let mut current: Option<String> = None;
+    let mut quoted = false;
+    for character in content.chars() {
+        match character {
+            '"' => {
+                quoted = !quoted;
+                current.get_or_insert_with(String::new);
+            }
+            character if character.is_ascii_whitespace() && !quoted => {
+                if let Some(token) = current.take() {
+                    tokens.push(token);
+                }
+            }
+            character => current.get_or_insert_with(String::new).push(character),
+        }
+    }
+    if let Some(token) = current {
+        tokens.push(token);
+    }
+    tokens
+}
+
 fn try_parse_flags<'a>(tokens: Vec<&'a str>) -> Result<HashMap<&'a str, Option<&'a str>>, String> {
     let mut flags: HashMap<&'a str, Option<&'a str>> = HashMap::new();
     let mut deque: VecDeque<&'a str> = tokens.into_iter().collect();
-    while deque.len() > 0 {
+    while !deque.is_empty() {
         if let Some(flag) = deque.pop_front() {
             if let Some(flag_name) = validate_flag(flag) {
                 if let Some(next) = deque.front() {
-                    if validate_flag(&next).is_some() {
+                    if validate_flag(next).is_some() {
                         flags.insert(flag_name, None);
                     } else {
                         let flag_value = deque.pop_front();
@@ -45,7 +66,7 @@ fn try_parse_flags<'a>(tokens: Vec<&'a str>) -> Result<HashMap<&'a str, Option<&
                     flags.insert(flag_name, None);
                 }
             } else {
-                return Err(format!("Invalid flag {}", flag.to_string()));
+                return Err(format!("Invalid flag {}", flag));
             }
         }
     }
@@ -53,7 +74,7 @@ fn try_parse_flags<'a>(tokens: Vec<&'a str>) -> Result<HashMap<&'a str, Option<&
 }
 
 fn validate_flag(flag: &str) -> Option<&str> {
-    if flag.len() == 0 {
+    if flag.is_empty() {
         return None;
     }
     let (first, rest) = flag.split_at(1);
@@ -0,0 +1,185 @@
+use crate::config::Config;
+use serenity::framework::standard::CommandResult;
+use serenity::framework::standard::macros::{command};
+use serenity::{
+    builder::{CreateEmbed, CreateMessage},
+    model::channel::Message,
+    prelude::Context,
+};
+use serde_json::Value;
+use tracing::error;
+
+#[command]
+pub async fn help(context: &Context, message: &Message) -> CommandResult {
+    let tokens: Vec<&str> = message.content.split_ascii_whitespace().collect();
+    let prefix = context.data.read().await.get::<Config>().unwrap().prefix.clone();
+    let create_message: for<'a, 'b> fn(&'a mut CreateMessage<'b>) -> &'a mut CreateMessage<'b> = match tokens.get(1) {
+        Some(&"user-settings") => create_settings_help_message,
+        Some(&"update-settings") => create_update_settings_help_message,
+        _ => create_help_message,
+    };
+    let sent_message = message
+        .channel_id
+        .send_message(&context.http, |m| with_prefix(create_message(m), &prefix))
+        .await;
+
+    if let Err(why) = sent_message {
+        error!("Error sending message: {:?}", why);
+    }
+    Ok(())
+}
+
+// The help text is written for the default ! prefix; swap in the configured
+// one wherever a command name follows it.
+fn with_prefix<'a, 'b>(message: &'a mut CreateMessage<'b>, prefix: &str) -> &'a mut CreateMessage<'b> {
+    if prefix != "!" {
+        for value in message.0.values_mut() {
+            replace_prefix(value, prefix);
+        }
+    }
+    message
+}
+
+fn replace_prefix(value: &mut Value, prefix: &str) {
+    match value {
+        Value::String(text) => {
+            let mut replaced = String::with_capacity(text.len());
+            let mut chars = text.chars().peekable();
+            while let Some(c) = chars.next() {
+                match (c, chars.peek()) {
+                    ('!', Some(next)) if next.is_ascii_lowercase() => replaced.push_str(prefix),
+                    _ => replaced.push(c),
+                }
+            }
+            *text = replaced;
+        }
+        Value::Array(values) => values.iter_mut().for_each(|value| replace_prefix(value, prefix)),
+        Value::Object(map) => map.values_mut().for_each(|value| replace_prefix(value, prefix)),
+        _ => {}
+    }
+}
+
+fn create_help_message<'a, 'b>(message: &'a mut CreateMessage<'b>) -> &'a mut CreateMessage<'b> {
+    message
+        .embed(|e| {
+            e.title("List of available commands:").fields(vec![
+                ("!recipe", "Gives the exact amount of machines needed to create an item.\nExample: *!recipe logistic science pack*\nAdd -a x to calculate for x items a second, or use a unit like 45/m or 2700/h.\nExample: *!recipe logistic science pack -a 5*\nSeveral items can be combined, each with its own rate.\nExample: *!recipe automation science pack 1, logistic science pack 1*\nAdd -pollution to include pollution per minute.\nAdd -tree to show which branch of the recipe needs which machines.\nAdd -import \"iron plate,electronic circuit\" to treat those items as raw.\nAdd -use \"recipe name\" to pick an alternative recipe.\nAdd -profile name to calculate with one of your saved profiles.", false),
+                ("!info", "Lists the recipes that make an item, including alternatives.\nExample: *!info solid fuel*", false),
+                ("!aliases", "Lists this server's nicknames for items, which work anywhere an item name does. Common ones like *green circuit* or *LDS* always work.\nAdmins can use *!add-alias \"green chip\" \"electronic circuit\"* and *!remove-alias \"green chip\"*.", false),
+                ("!user-settings", "Displays your current user settings, which modify !recipe calculations, and whether each comes from you, this channel, this server or the defaults.\nType *!help user-settings* for more information.", false),
+                ("!update-settings", "Updates user settings.\nType *!help update-settings* for more information.", false),
+                ("!profile", "Saves your settings under a name to switch back to later.\n*!profile save megabase* saves your current settings.\n*!profile use early* switches to a saved profile.\n*!profile list* and *!profile delete name* manage them.", false),
+                ("!claim-settings", "Moves settings you saved before they were tied to your account, under your user name, to your account.\nBot owners can move someone's by name with *!claim-settings \"name\" @user*.", false),
+                ("!server-settings", "Admins only. Shows or changes the defaults for everyone in this server, using the same flags as !update-settings.\nExample: *!server-settings -a 3 -f steel*", false),
+                ("!channel-settings", "Admins only. Shows or changes the defaults for this channel, which override the server's, using the same flags as !update-settings.", false)
+            ])
+    })
+}
+
+fn create_settings_help_message<'a, 'b>(
+    message: &'a mut CreateMessage<'b>,
+) -> &'a mut CreateMessage<'b> {
+    message.embed(|e| {
+        e.title("User settings:");
+        add_user_settings_flags(e)
+    })
+}
+
+fn create_update_settings_help_message<'a, 'b>(
+    message: &'a mut CreateMessage<'b>,
+) -> &'a mut CreateMessage<'b> {
+    message
+        .embed(|e| {
+            e.title("Update settings:")
+                .description("Updates user settings which are used to calculate ratios.\n\nExample: *!update-settings -a 3 -f stone*\n\nAvailable flags:");
+            add_user_settings_flags(e)
+        })
+}
+
+fn add_user_settings_flags(embed: &mut CreateEmbed) -> &mut CreateEmbed {
+    embed.fields(vec![
+        ("-a", "Assembling machine level.\nValues: *1, 2, 3*", false),
+        ("-f", "Furnace level.\nValues: *Stone, Steel, Electric*", false),
+        ("-drill", "Mining drill used for ore.\nValues: *Burner, Electric*", false),
+        (
+            "-mining-productivity",
+            "Mining productivity research level, each adding 10% to drills and pumpjacks.\nValues: *0, 1, 2, ...*",
+            false,
+        ),
+        (
+            "-pumpjack-yield",
+            "Yield of crude oil fields in percent.\nValues: *100, 250, ...*",
+            false,
+        ),
+        (
+            "-belt",
+            "Belt used for throughput estimates.\nValues: *Yellow, Red, Blue*",
+            false,
+        ),
+        (
+            "-unit",
+            "Time unit used to show rates in !recipe results.\nValues: *s, m, h*",
+            false,
+        ),
+        (
+            "-tree-depth",
+            "Levels of the !recipe -tree breakdown shown before subtrees are collapsed.\nValues: *1, 2, 3, ...*",
+            false,
+        ),
+        (
+            "-w",
+            "Adjust ratios to all be whole numbers.\nValues: *true, false*",
+            false,
+        ),
+        (
+            "-pollution",
+            "Always show the pollution per minute of !recipe results.\nValues: *true, false*",
+            false,
+        ),
+        (
+            "-ignore-furnace",
+            "Ignore furnaces when adjusting ratios to whole numbers.\nValues: *true, false*",
+            false,
+        ),
+        (
+            "-ignore-raw",
+            "Ignore raw materials when adjusting ratios to whole numbers.\nValues: *true, false*",
+            false,
+        ),
+        (
+            "-import",
+            "Comma separated items taken from a main bus, which !recipe treats as raw materials.\nExample: *-import \"electronic circuit,iron plate\"*\nUse *none* to clear the list.",
+            false,
+        ),
+        (
+            "-use",
+            "Comma separated recipes to always use for the items they make, see !info for the alternatives.\nExample: *-use \"basic oil processing\"*\nUse *none* to go back to the cheapest recipes.",
+            false,
+        ),
+        (
+            "-producer",
+            "Machine whose default modules and beacons are being set.\nValues: *am, f, cp, or, c, rs*",
+            false,
+        ),
+        (
+            "-item",
+            "Item whose machine tier, modules and beacons override the defaults. With -item, -a and -f only apply to that item, and *default* removes the override.\nExample: *-item \"electronic circuit\" -a 3 -modules p3,p3,p3,p3*",
+            false,
+        ),
+        (
+            "-modules",
+            "Comma separated modules, used with -producer or -item. Productivity modules are only used in recipes that allow them.\nValues: *s1-s3, p1-p3, e1-e3, none, default*",
+            false,
+        ),
+        (
+            "-beacons",
+            "Beacons (each with two speed module 3s) affecting every machine, used with -producer or -item.\nValues: *0, 1, 2, ...*",
+            false,
+        ),
+        (
+            "-reset",
+            "Used on its own, forgets every setting changed at this level so the channel, server or built in defaults apply again.",
+            false,
+        ),
+    ])
+}
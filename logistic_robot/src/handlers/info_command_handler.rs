@@ -0,0 +1,124 @@
+use serenity::framework::standard::CommandResult;
+use serenity::framework::standard::macros::{command};
+use crate::guild_aliases::GuildAliasDatabase;
+use crate::handlers::flags::tokenize;
+use logistic_robot_core::format::print_amount;
+use logistic_robot_core::materials::material::Material;
+use crate::material_database_key::MaterialDatabaseKey;
+use logistic_robot_core::materials::material_database::MaterialDatabase;
+use logistic_robot_core::recipe::Recipe;
+use crate::user_settings::{UserSettings, UserSettingsDatabase};
+use fraction::Fraction;
+use serenity::{model::channel::Message, prelude::Context};
+use std::collections::HashMap;
+use tracing::error;
+
+#[command]
+pub async fn info(context: &Context, message: &Message) -> CommandResult {
+    let tokens: Vec<String> = tokenize(&message.content);
+    let item_name = tokens.iter().skip(1).cloned().collect::<Vec<String>>().join(" ");
+    let data = context.data.read().await;
+    let user_settings_database = data.get::<UserSettingsDatabase>().unwrap();
+    let material_database = data.get::<MaterialDatabaseKey>().unwrap();
+    let guild_aliases = data.get::<GuildAliasDatabase>().unwrap().get(message.guild_id);
+    let user_settings = user_settings_database.resolve(message).settings;
+    let possible_material = material_database.lookup_material(&item_name, &guild_aliases);
+    let sent_message = message.channel_id.send_message(&context.http, |m| {
+        match possible_material {
+            Ok(material) => m.embed(|e| {
+                e.title(&material.name);
+                let mut description: Vec<String> = Vec::new();
+                if material.recipes.is_empty() {
+                    description.push(generate_raw_description(material));
+                }
+                if let Some(alias_description) = generate_alias_description(material, &guild_aliases) {
+                    description.push(alias_description);
+                }
+                if !description.is_empty() {
+                    e.description(description.join("\n"));
+                }
+                for &recipe_index in &material.recipes {
+                    let recipe = material_database.recipe(recipe_index);
+                    e.field(
+                        format!(
+                            "{}{}",
+                            recipe.name,
+                            get_recipe_status(&user_settings, material, recipe)
+                        ),
+                        generate_recipe_description(material_database, recipe),
+                        false,
+                    );
+                }
+                if material.recipes.len() > 1 {
+                    e.footer(|f| {
+                        f.text("Pick a recipe with -use \"recipe name\" on !recipe or !update-settings.")
+                    });
+                }
+                e
+            }),
+            Err(why) => m.content(why.to_string()),
+        }
+    });
+
+    if let Err(why) = sent_message.await {
+        error!("Unable to send message: {}", why);
+    }
+    Ok(())
+}
+
+fn get_recipe_status(user_settings: &UserSettings, material: &Material, recipe: &Recipe) -> String {
+    match user_settings.recipe_choice(material) {
+        Some(choice) if choice.to_lowercase() == recipe.name.to_lowercase() => " (your choice)".to_string(),
+        Some(_choice) => " (not used)".to_string(),
+        None if !recipe.enabled => " (disabled by default)".to_string(),
+        None => "".to_string(),
+    }
+}
+
+fn generate_alias_description(
+    material: &Material,
+    guild_aliases: &HashMap<String, String>,
+) -> Option<String> {
+    let mut aliases: Vec<String> = material.aliases.clone();
+    let mut server_aliases: Vec<String> = guild_aliases
+        .iter()
+        .filter(|(_alias, material_name)| **material_name == material.name)
+        .map(|(alias, _material_name)| alias.clone())
+        .collect();
+    server_aliases.sort();
+    aliases.extend(server_aliases);
+    match aliases.is_empty() {
+        true => None,
+        false => Some(format!("Also known as: {}", aliases.join(", "))),
+    }
+}
+
+fn generate_raw_description(material: &Material) -> String {
+    match &material.extraction {
+        Some(extraction) => format!(
+            "Raw material, extracted with a {}.",
+            extraction.extractor_type
+        ),
+        None => "Raw material.".to_string(),
+    }
+}
+
+fn generate_recipe_description(material_database: &MaterialDatabase, recipe: &Recipe) -> String {
+    format!(
+        "{}, {} s\n{} → {}",
+        recipe.producer_type,
+        print_amount(recipe.time),
+        describe_resources(material_database, &recipe.ingredients),
+        describe_resources(material_database, &recipe.products)
+    )
+}
+
+fn describe_resources(material_database: &MaterialDatabase, resources: &[(usize, Fraction)]) -> String {
+    resources
+        .iter()
+        .map(|(material_index, amount)| {
+            format!("{} {}", print_amount(*amount), material_database.material(*material_index).name)
+        })
+        .collect::<Vec<String>>()
+        .join(" + ")
+}
@@ -0,0 +1,10 @@
+pub mod alias_command_handler;
+pub mod claim_settings_command_handler;
+pub mod default_settings_command_handler;
+pub mod flags;
+pub mod help_command_handler;
+pub mod info_command_handler;
+pub mod profile_command_handler;
+pub mod recipe_command_handler;
+pub mod user_settings_command_handler;
+pub mod update_settings_command_handler;
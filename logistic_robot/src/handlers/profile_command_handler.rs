@@ -0,0 +1,190 @@
+use serenity::framework::standard::CommandResult;
+use serenity::framework::standard::macros::{command};
+use crate::handlers::flags::tokenize;
+use crate::user_settings::{SettingsLevel, UserSettingsDatabase};
+use serenity::{model::channel::Message, prelude::*};
+use tracing::error;
+
+#[command]
+#[aliases("profiles")]
+pub async fn profile(context: &Context, message: &Message) -> CommandResult {
+    let response = {
+        let mut data = context.data.write().await;
+        let user_settings_database = data.get_mut::<UserSettingsDatabase>().unwrap();
+        run_profile_command(user_settings_database, message)
+    };
+
+    if let Err(why) = message.channel_id.say(&context.http, response).await {
+        error!("Unable to send message: {}", why);
+    }
+    Ok(())
+}
+
+fn run_profile_command(user_settings_database: &mut UserSettingsDatabase, message: &Message) -> String {
+    let tokens: Vec<String> = tokenize(&message.content);
+    let name = tokens.iter().skip(2).cloned().collect::<Vec<String>>().join(" ");
+    let subcommand = tokens.get(1).map(|token| token.to_lowercase());
+    match (subcommand.as_deref(), name.is_empty()) {
+        (Some("save"), false) => {
+            let user_settings = user_settings_database.resolve(message).settings;
+            user_settings_database
+                .update_profiles(&message.author, |profiles| profiles.insert(&name, user_settings))
+                .map(|()| format!("Saved your current settings as profile *{}*.", name))
+                .unwrap_or_else(|why| why)
+        }
+        (Some("use"), false) => {
+            match user_settings_database.profiles(&message.author).get(&name) {
+                Some((profile_name, profile_settings)) => {
+                    let profile_name = profile_name.clone();
+                    let profile_settings = profile_settings.clone();
+                    user_settings_database.update(message, SettingsLevel::User, |user_settings| {
+                        *user_settings = profile_settings;
+                    });
+                    user_settings_database.update_profiles(&message.author, |profiles| {
+                        profiles.active = Some(profile_name.clone());
+                    });
+                    format!("Now using profile *{}*.", profile_name)
+                }
+                None => describe_missing_profile(user_settings_database, message, &name),
+            }
+        }
+        (Some("delete"), false) => {
+            match user_settings_database.update_profiles(&message.author, |profiles| profiles.remove(&name)) {
+                Some(profile_name) => format!("Deleted profile *{}*.", profile_name),
+                None => describe_missing_profile(user_settings_database, message, &name),
+            }
+        }
+        (Some("list"), true) | (None, true) => {
+            describe_profiles(user_settings_database, message)
+        }
+        _ => "Invalid syntax. Use *!profile save name*, *!profile use name*, *!profile delete name* or *!profile list*.".to_string(),
+    }
+}
+
+fn describe_profiles(user_settings_database: &UserSettingsDatabase, message: &Message) -> String {
+    let profiles = user_settings_database.profiles(&message.author);
+    if profiles.is_empty() {
+        return "You have no profiles yet. Save your current settings with *!profile save name*.".to_string();
+    }
+    let current_settings = serde_json::to_value(user_settings_database.resolve(message).settings).ok();
+    let lines: Vec<String> = profiles
+        .profiles
+        .iter()
+        .map(|(profile_name, profile_settings)| {
+            match profiles.active.as_ref() == Some(profile_name) {
+                true if serde_json::to_value(profile_settings).ok() == current_settings => {
+                    format!("**{}** (active)", profile_name)
+                }
+                true => format!("**{}** (active, changed since)", profile_name),
+                false => profile_name.clone(),
+            }
+        })
+        .collect();
+    format!("Your profiles:\n{}", lines.join("\n"))
+}
+
+fn describe_missing_profile(
+    user_settings_database: &UserSettingsDatabase,
+    message: &Message,
+    name: &str,
+) -> String {
+    let profiles = user_settings_database.profiles(&message.author);
+    match profiles.is_empty() {
+        true => format!("Error: No profile named *{}*. You have no profiles yet.", name),
+        false => format!(
+            "Error: No profile named *{}*. Your profiles are: {}",
+            name,
+            profiles.profiles.keys().cloned().collect::<Vec<String>>().join(", ")
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::handlers::recipe_command_handler::calculation_settings;
+    use crate::storage::open_storage;
+    use crate::user_settings::tests::{message, storage_file};
+    use logistic_robot_core::enums::FurnaceLevel;
+    use serde_json::json;
+
+    fn run(user_settings_database: &mut UserSettingsDatabase, content: &str) -> String {
+        run_profile_command(user_settings_database, &message(1, 2, 3, content))
+    }
+
+    fn set_furnace_level(user_settings_database: &mut UserSettingsDatabase, furnace_level: FurnaceLevel) {
+        user_settings_database.update(&message(1, 2, 3, "!update-settings"), SettingsLevel::User, |settings| {
+            settings.furnace_level = furnace_level;
+        });
+    }
+
+    #[test]
+    fn saves_uses_lists_and_deletes_profiles() {
+        let path = storage_file("profiles", json!({}));
+        let mut user_settings_database = UserSettingsDatabase::load(open_storage("json", &path).unwrap()).unwrap();
+        let author = message(1, 2, 3, "").author;
+        assert_eq!(
+            run(&mut user_settings_database, "!profile list"),
+            "You have no profiles yet. Save your current settings with *!profile save name*."
+        );
+
+        set_furnace_level(&mut user_settings_database, FurnaceLevel::Electric);
+        assert_eq!(
+            run(&mut user_settings_database, "!profile save Megabase"),
+            "Saved your current settings as profile *Megabase*."
+        );
+        set_furnace_level(&mut user_settings_database, FurnaceLevel::Steel);
+        assert_eq!(
+            run(&mut user_settings_database, "!profile list"),
+            "Your profiles:\n**Megabase** (active, changed since)"
+        );
+
+        assert_eq!(run(&mut user_settings_database, "!profile use megabase"), "Now using profile *Megabase*.");
+        let resolved = user_settings_database.resolve(&message(1, 2, 3, ""));
+        assert!(matches!(resolved.settings.furnace_level, FurnaceLevel::Electric));
+        assert_eq!(run(&mut user_settings_database, "!profile list"), "Your profiles:\n**Megabase** (active)");
+
+        set_furnace_level(&mut user_settings_database, FurnaceLevel::Stone);
+        run(&mut user_settings_database, "!profile save Early");
+        assert_eq!(
+            run(&mut user_settings_database, "!profile list"),
+            "Your profiles:\n**Early** (active)\nMegabase"
+        );
+
+        assert_eq!(run(&mut user_settings_database, "!profile delete early"), "Deleted profile *Early*.");
+        assert_eq!(
+            run(&mut user_settings_database, "!profile delete Early"),
+            "Error: No profile named *Early*. Your profiles are: Megabase"
+        );
+        assert_eq!(run(&mut user_settings_database, "!profile list"), "Your profiles:\nMegabase");
+
+        let reloaded = UserSettingsDatabase::load(open_storage("json", &path).unwrap()).unwrap();
+        let profiles = reloaded.profiles(&author);
+        assert_eq!(profiles.profiles.keys().collect::<Vec<&String>>(), vec!["Megabase"]);
+        assert_eq!(profiles.active, None);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn recipe_profile_flag_leaves_the_active_profile_alone() {
+        let path = storage_file("profile_flag", json!({}));
+        let mut user_settings_database = UserSettingsDatabase::load(open_storage("json", &path).unwrap()).unwrap();
+        let recipe_message = message(1, 2, 3, "!recipe inserter -profile megabase");
+        set_furnace_level(&mut user_settings_database, FurnaceLevel::Electric);
+        run(&mut user_settings_database, "!profile save Megabase");
+        set_furnace_level(&mut user_settings_database, FurnaceLevel::Stone);
+        run(&mut user_settings_database, "!profile save Early");
+
+        let settings = calculation_settings(&user_settings_database, &recipe_message, Some("megabase")).unwrap();
+        assert!(matches!(settings.furnace_level, FurnaceLevel::Electric));
+        let resolved = user_settings_database.resolve(&recipe_message);
+        assert!(matches!(resolved.settings.furnace_level, FurnaceLevel::Stone));
+        assert_eq!(user_settings_database.profiles(&recipe_message.author).active.as_deref(), Some("Early"));
+
+        assert_eq!(
+            calculation_settings(&user_settings_database, &recipe_message, Some("late")).err(),
+            Some("Error: No profile named *late*".to_string())
+        );
+        std::fs::remove_file(&path).unwrap();
+    }
+}
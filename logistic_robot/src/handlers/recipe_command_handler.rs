@@ -0,0 +1,175 @@
+use serenity::framework::standard::CommandResult;
+use serenity::framework::standard::macros::{command};
+use crate::guild_aliases::GuildAliasDatabase;
+use crate::handlers::flags::tokenize;
+use crate::material_database_key::MaterialDatabaseKey;
+use crate::user_settings::{UserSettings, UserSettingsDatabase};
+use fraction::Fraction;
+use logistic_robot_core::format;
+use logistic_robot_core::rate::parse_rate;
+use logistic_robot_core::{Calculation, Calculator, CalculatorError};
+use serenity::{builder::CreateEmbed, model::channel::Message, prelude::Context};
+use tracing::error;
+
+// Discord rejects embed fields and descriptions longer than these.
+const MAX_FIELD_LENGTH: usize = 1024;
+const MAX_DESCRIPTION_LENGTH: usize = 4096;
+
+#[command]
+pub async fn recipe(context: &Context, message: &Message) -> CommandResult {
+    let tokens_with_command: Vec<String> = tokenize(&message.content);
+    let tokens_with_flags: Vec<&str> =
+        tokens_with_command.iter().skip(1).map(|token| token.as_str()).collect();
+    let show_pollution_flag = tokens_with_flags.contains(&"-pollution");
+    let show_tree = tokens_with_flags.contains(&"-tree");
+    let mut import_names: Result<Option<&str>, String> = Ok(None);
+    let mut recipe_names: Result<Option<&str>, String> = Ok(None);
+    let mut profile_name: Result<Option<&str>, String> = Ok(None);
+    let mut tokens: Vec<&str> = Vec::new();
+    let mut token_iter = tokens_with_flags.iter();
+    while let Some(token) = token_iter.next() {
+        match *token {
+            "-pollution" | "-tree" => {}
+            "-import" => {
+                import_names = token_iter
+                    .next()
+                    .map(|import_names| Some(*import_names))
+                    .ok_or_else(|| "No value found for -import flag".to_string());
+            }
+            "-use" => {
+                recipe_names = token_iter
+                    .next()
+                    .map(|recipe_names| Some(*recipe_names))
+                    .ok_or_else(|| "No value found for -use flag".to_string());
+            }
+            "-profile" => {
+                profile_name = token_iter
+                    .next()
+                    .map(|profile_name| Some(*profile_name))
+                    .ok_or_else(|| "No value found for -profile flag".to_string());
+            }
+            token => tokens.push(token),
+        }
+    }
+    let mut amount = Ok(Fraction::from(1));
+    let command;
+    if tokens.len() > 2 && tokens[tokens.len() - 2] == "-a" {
+        let split = tokens.split_at(tokens.len() - 2);
+        command = split.0;
+        amount = parse_rate(split.1[1]);
+    }
+    else {
+        command = &tokens;
+    }
+    let data = context.data.write().await;
+    let user_settings_database = data.get::<UserSettingsDatabase>().unwrap();
+    let material_database = data.get::<MaterialDatabaseKey>().unwrap();
+    let guild_aliases = data.get::<GuildAliasDatabase>().unwrap().get(message.guild_id);
+    let item_names = command.join(" ");
+    let possible_calculation = amount
+        .map_err(|why| format!("Error: {}", why))
+        .and_then(|amount| {
+            let user_settings = calculation_settings(user_settings_database, message, profile_name?)?;
+            let mut calculator = Calculator::new(material_database)
+                .settings(user_settings)
+                .aliases(guild_aliases)
+                .items(&item_names)
+                .rate(amount);
+            if let Some(import_names) = import_names? {
+                calculator = calculator.imports(import_names);
+            }
+            if let Some(recipe_names) = recipe_names? {
+                calculator = calculator.recipes(recipe_names);
+            }
+            calculator.calculate().map_err(|why| match why {
+                CalculatorError::InvalidRate(why) => format!("Error: {}", why),
+                CalculatorError::Unsolvable(why) => {
+                    format!("Unable to calculate *{}*: {}", item_names, why)
+                }
+                why => why.to_string(),
+            })
+        });
+    let sent_message = message.channel_id.send_message(&context.http, |m| {
+        match possible_calculation {
+            Ok(calculation) => m.embed(|e| {
+                e.title(format::title(&calculation))
+                    .description(match show_tree {
+                        true => generate_tree_description(&calculation),
+                        false => format::description(&calculation),
+                    });
+                if let Some(module_description) = format::module_description(&calculation) {
+                    e.field("Modules:", module_description, false);
+                }
+                if let Some(module_warnings) = format::module_warnings(&calculation) {
+                    e.field("Modules left out:", module_warnings, false);
+                }
+                if !calculation.power.is_empty() {
+                    e.field("Power:", format::power_description(&calculation), false);
+                }
+                if !calculation.item_rates.is_empty() {
+                    add_split_field(e, "Belts:", format::belt_lines(&calculation));
+                }
+                if show_pollution_flag || calculation.settings.show_pollution {
+                    e.field("Pollution:", format::pollution_description(&calculation), false);
+                }
+                e.footer(|f| f.text(format::footer(&calculation)))
+            }),
+            Err(why) => m.content(why),
+        }
+    });
+
+    if let Err(why) = sent_message.await {
+        error!("Unable to send message: {}", why);
+    }
+
+    Ok(())
+}
+
+// A profile replaces the settings for this calculation only.
+pub(crate) fn calculation_settings(
+    user_settings_database: &UserSettingsDatabase,
+    message: &Message,
+    profile_name: Option<&str>,
+) -> Result<UserSettings, String> {
+    match profile_name {
+        Some(profile_name) => match user_settings_database.profiles(&message.author).get(profile_name) {
+            Some((_profile_name, profile_settings)) => Ok(profile_settings.clone()),
+            None => Err(format!("Error: No profile named *{}*", profile_name)),
+        },
+        None => Ok(user_settings_database.resolve(message).settings),
+    }
+}
+
+fn generate_tree_description(calculation: &Calculation) -> String {
+    let mut description = String::new();
+    for line in format::tree_lines(calculation) {
+        if description.len() + line.len() + 1 > MAX_DESCRIPTION_LENGTH - 100 {
+            description.push_str("…\nThe tree was cut short, lower -tree-depth to see all of it.");
+            break;
+        }
+        description.push_str(&line);
+        description.push('\n');
+    }
+    description
+}
+
+// Spreads the lines over as many fields as needed to stay under the length
+// limit, naming the extra fields "(continued)".
+fn add_split_field(embed: &mut CreateEmbed, name: &str, lines: Vec<String>) {
+    let mut chunks: Vec<String> = Vec::new();
+    for line in lines {
+        match chunks.last_mut() {
+            Some(chunk) if chunk.len() + line.len() < MAX_FIELD_LENGTH => {
+                chunk.push('\n');
+                chunk.push_str(&line);
+            }
+            _ => chunks.push(line),
+        }
+    }
+    for (index, chunk) in chunks.into_iter().enumerate() {
+        match index {
+            0 => embed.field(name, chunk, false),
+            _ => embed.field(format!("{} (continued)", name.trim_end_matches(':')), chunk, false),
+        };
+    }
+}
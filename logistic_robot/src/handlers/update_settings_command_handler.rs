@@ -0,0 +1,573 @@
+use serenity::framework::standard::CommandResult;
+use serenity::framework::standard::macros::{command};
+use logistic_robot_core::enums::{
+    self, AssemblingMachineLevel, BeltTier, FurnaceLevel, MiningDrillLevel, Module, ProducerType,
+    TimeUnit,
+};
+use crate::guild_aliases::GuildAliasDatabase;
+use crate::handlers::flags::{tokenize, Flags};
+use crate::material_database_key::MaterialDatabaseKey;
+use logistic_robot_core::materials::material_database::MaterialDatabase;
+use logistic_robot_core::modules::{module_slots, ModuleConfiguration};
+use logistic_robot_core::rate::parse_time_unit;
+use logistic_robot_core::recipe::Recipe;
+use crate::user_settings::{LevelOverride, SettingsLevel, UserSettings, UserSettingsDatabase};
+use serenity::{model::channel::Message, prelude::*};
+use std::collections::HashMap;
+use tracing::error;
+
+enum ModuleTarget {
+    Producer(ProducerType),
+    Item(String, ProducerType),
+}
+
+#[command]
+#[aliases("update-settings")]
+pub async fn update_settings(context: &Context, message: &Message) -> CommandResult {
+    update_settings_at_level(context, message, SettingsLevel::User, "User settings updated successfully.").await
+}
+
+// Shared by !update-settings and the server and channel versions: applies the
+// flags to that level's settings, or clears the level with -reset.
+pub async fn update_settings_at_level(
+    context: &Context,
+    message: &Message,
+    level: SettingsLevel,
+    success_message: &str,
+) -> CommandResult {
+    let tokens_with_command: Vec<String> = tokenize(&message.content);
+    let tokens: Vec<&str> = tokens_with_command.iter().skip(1).map(|token| token.as_str()).collect();
+    let mut data = context.data.write().await;
+    let material_database = data.get::<MaterialDatabaseKey>().unwrap().clone();
+    let guild_aliases = data.get::<GuildAliasDatabase>().unwrap().get(message.guild_id);
+    let user_settings_database = data.get_mut::<UserSettingsDatabase>().unwrap();
+    let response = match tokens.as_slice() {
+        ["-reset"] => {
+            user_settings_database.reset(message, level);
+            format!("Settings reset, the {} defaults apply again.", match level {
+                SettingsLevel::User => "channel and server",
+                SettingsLevel::Channel => "server",
+                _ => "built in",
+            })
+        }
+        _ => match user_settings_database.update(message, level, |user_settings| {
+            apply_settings_flags(user_settings, &tokens, &material_database, &guild_aliases)
+        }) {
+            Ok(()) => success_message.to_string(),
+            Err(why) => why,
+        },
+    };
+
+    if let Err(why) = message.channel_id.say(&context.http, response).await {
+        error!("Unable to send message: {}", why);
+    }
+    Ok(())
+}
+
+// Leaves the settings untouched unless every flag is valid.
+fn apply_settings_flags(
+    user_settings: &mut UserSettings,
+    tokens: &[&str],
+    material_database: &MaterialDatabase,
+    guild_aliases: &HashMap<String, String>,
+) -> Result<(), String> {
+    let flags = Flags::new(tokens.to_vec()).map_err(|_| {
+        "Invalid syntax.  Available flags are: -a, -f, -w, -ignore-furnace, -ignore-raw, -producer, -item, -modules, -beacons, -drill, -mining-productivity, -pumpjack-yield, -belt, -unit, -tree-depth, -import, -use, -pollution, -reset.  Type *-help update-settings* for help.".to_string()
+    })?;
+    for (flag_name, _possible_flag_value) in flags.get_all() {
+        match flag_name {
+            "a" | "f" | "w" | "ignore-furnace" | "ignore-raw" | "producer" | "item"
+            | "modules" | "beacons" | "drill" | "mining-productivity" | "pumpjack-yield"
+            | "pollution" | "belt" | "unit" | "tree-depth" | "import" | "use" => {},
+            _ => return Err(format!("Error: Unknown flag *-{}*", flag_name)),
+        }
+    }
+    let module_target = get_module_target(user_settings, &flags, material_database, guild_aliases)?;
+    // With -item, -a and -f only change the tiers used for that item.
+    let item_target = matches!(module_target, Some(ModuleTarget::Item(_, _)));
+    let assembling_machine_level = match item_target {
+        true => user_settings.assembling_machine_level,
+        false => get_assembling_machine_level(user_settings, &flags)?,
+    };
+    let furnace_level = match item_target {
+        true => user_settings.furnace_level,
+        false => get_furnace_level(user_settings, &flags)?,
+    };
+    let level_overrides = get_level_overrides(user_settings, &flags, &module_target)?;
+    let mining_drill_level = get_mining_drill_level(user_settings, &flags)?;
+    let mining_productivity_level = get_mining_productivity_level(user_settings, &flags)?;
+    let pumpjack_yield = get_pumpjack_yield(user_settings, &flags)?;
+    let belt_tier = get_belt_tier(user_settings, &flags)?;
+    let display_unit = get_display_unit(user_settings, &flags)?;
+    let tree_depth = get_tree_depth(user_settings, &flags)?;
+    let imports = get_imports(user_settings, &flags, material_database, guild_aliases)?;
+    let recipe_choices = get_recipe_choices(user_settings, &flags, material_database)?;
+    let whole_numbers = get_whole_number(user_settings, &flags)?;
+    let show_pollution = get_show_pollution(user_settings, &flags)?;
+    let ignore_furnaces_for_ratio = get_ignore_furnace(user_settings, &flags)?;
+    let ignore_raw_for_ratio = get_ignore_raw(user_settings, &flags)?;
+    let module_configurations = get_module_configurations(user_settings, &flags, &module_target)?;
+    let module_overrides = get_module_overrides(user_settings, &flags, &module_target)?;
+
+    let mut updated_settings = user_settings.clone();
+    updated_settings.assembling_machine_level = assembling_machine_level;
+    updated_settings.furnace_level = furnace_level;
+    updated_settings.mining_drill_level = mining_drill_level;
+    updated_settings.mining_productivity_level = mining_productivity_level;
+    updated_settings.pumpjack_yield = pumpjack_yield;
+    updated_settings.belt_tier = belt_tier;
+    updated_settings.display_unit = display_unit;
+    updated_settings.tree_depth = tree_depth;
+    updated_settings.imports = imports;
+    updated_settings.recipe_choices = recipe_choices;
+    updated_settings.whole_numbers = whole_numbers;
+    updated_settings.show_pollution = show_pollution;
+    updated_settings.ignore_furnaces_for_ratio = ignore_furnaces_for_ratio;
+    updated_settings.ignore_raw_for_ratio = ignore_raw_for_ratio;
+    updated_settings.module_configurations = module_configurations;
+    updated_settings.module_overrides = module_overrides;
+    updated_settings.level_overrides = level_overrides;
+    updated_settings
+        .validate(material_database)
+        .map_err(|why| format!("Error: {}", why))?;
+    *user_settings = updated_settings;
+    Ok(())
+}
+
+fn get_assembling_machine_level(
+    user_settings: &UserSettings,
+    flags: &Flags,
+) -> Result<AssemblingMachineLevel, String> {
+    match flags.get("a") {
+        Some(possible_flag_value) => match possible_flag_value {
+            Some(flag_value) => parse_assembling_machine_level(flag_value),
+            None => Err("No value found for -a flag".to_string()),
+        },
+        None => Ok(user_settings.assembling_machine_level),
+    }
+}
+
+fn parse_assembling_machine_level(flag_value: &str) -> Result<AssemblingMachineLevel, String> {
+    enums::parse_assembling_machine_level(flag_value).ok_or_else(|| {
+        format!(
+            "Error: Invalid assembling machine level *{}*",
+            flag_value
+        )
+    })
+}
+
+fn get_furnace_level(user_settings: &UserSettings, flags: &Flags) -> Result<FurnaceLevel, String> {
+    match flags.get("f") {
+        Some(possible_flag_value) => match possible_flag_value {
+            Some(flag_value) => parse_furnace_level(flag_value),
+            None => Err("No value found for -f flag".to_string()),
+        },
+        None => Ok(user_settings.furnace_level),
+    }
+}
+
+fn parse_furnace_level(flag_value: &str) -> Result<FurnaceLevel, String> {
+    enums::parse_furnace_level(flag_value).ok_or_else(|| {
+        format!(
+            "Error: Invalid furnace level flag *{}*",
+            flag_value
+        )
+    })
+}
+
+fn get_mining_drill_level(
+    user_settings: &UserSettings,
+    flags: &Flags,
+) -> Result<MiningDrillLevel, String> {
+    match flags.get("drill") {
+        Some(possible_flag_value) => match possible_flag_value {
+            Some(flag_value) => match &*flag_value.to_lowercase() {
+                "burner" => Ok(MiningDrillLevel::Burner),
+                "electric" => Ok(MiningDrillLevel::Electric),
+                _ => Err(format!("Error: Invalid mining drill *{}*", flag_value)),
+            },
+            None => Err("No value found for -drill flag".to_string()),
+        },
+        None => Ok(user_settings.mining_drill_level),
+    }
+}
+
+fn get_mining_productivity_level(user_settings: &UserSettings, flags: &Flags) -> Result<u32, String> {
+    match flags.get("mining-productivity") {
+        Some(possible_flag_value) => match possible_flag_value {
+            Some(flag_value) => match flag_value.parse::<u32>() {
+                Ok(level) => Ok(level),
+                Err(_) => Err(format!(
+                    "Error: Invalid mining productivity level *{}*",
+                    flag_value
+                )),
+            },
+            None => Err("No value found for -mining-productivity flag".to_string()),
+        },
+        None => Ok(user_settings.mining_productivity_level),
+    }
+}
+
+fn get_pumpjack_yield(user_settings: &UserSettings, flags: &Flags) -> Result<u64, String> {
+    match flags.get("pumpjack-yield") {
+        Some(possible_flag_value) => match possible_flag_value {
+            Some(flag_value) => match flag_value.trim_end_matches('%').parse::<u64>() {
+                Ok(pumpjack_yield) if pumpjack_yield > 0 => Ok(pumpjack_yield),
+                _ => Err(format!("Error: Invalid pumpjack yield *{}*", flag_value)),
+            },
+            None => Err("No value found for -pumpjack-yield flag".to_string()),
+        },
+        None => Ok(user_settings.pumpjack_yield),
+    }
+}
+
+fn get_belt_tier(user_settings: &UserSettings, flags: &Flags) -> Result<BeltTier, String> {
+    match flags.get("belt") {
+        Some(possible_flag_value) => match possible_flag_value {
+            Some(flag_value) => match enums::parse_belt_tier(flag_value) {
+                Some(belt_tier) => Ok(belt_tier),
+                None => Err(format!("Error: Invalid belt *{}*", flag_value)),
+            },
+            None => Err("No value found for -belt flag".to_string()),
+        },
+        None => Ok(user_settings.belt_tier),
+    }
+}
+
+fn get_display_unit(user_settings: &UserSettings, flags: &Flags) -> Result<TimeUnit, String> {
+    match flags.get("unit") {
+        Some(possible_flag_value) => match possible_flag_value {
+            Some(flag_value) => match parse_time_unit(flag_value) {
+                Some(display_unit) => Ok(display_unit),
+                None => Err(format!("Error: Invalid time unit *{}*", flag_value)),
+            },
+            None => Err("No value found for -unit flag".to_string()),
+        },
+        None => Ok(user_settings.display_unit),
+    }
+}
+
+fn get_tree_depth(user_settings: &UserSettings, flags: &Flags) -> Result<u32, String> {
+    match flags.get("tree-depth") {
+        Some(possible_flag_value) => match possible_flag_value {
+            Some(flag_value) => match flag_value.parse::<u32>() {
+                Ok(tree_depth) if tree_depth > 0 => Ok(tree_depth),
+                _ => Err(format!("Error: Invalid tree depth *{}*", flag_value)),
+            },
+            None => Err("No value found for -tree-depth flag".to_string()),
+        },
+        None => Ok(user_settings.tree_depth),
+    }
+}
+
+fn get_imports(
+    user_settings: &UserSettings,
+    flags: &Flags,
+    material_database: &MaterialDatabase,
+    guild_aliases: &HashMap<String, String>,
+) -> Result<Vec<String>, String> {
+    match flags.get("import") {
+        Some(possible_flag_value) => match possible_flag_value {
+            Some(flag_value) if flag_value.to_lowercase() == "none" => Ok(Vec::new()),
+            Some(flag_value) => match material_database.find_materials(flag_value, guild_aliases) {
+                Ok(materials) => Ok(materials
+                    .into_iter()
+                    .map(|material| material.name.clone())
+                    .collect()),
+                Err(why) => Err(format!("Error: {}", why)),
+            },
+            None => Err("No value found for -import flag".to_string()),
+        },
+        None => Ok(user_settings.imports.clone()),
+    }
+}
+
+fn get_recipe_choices(
+    user_settings: &UserSettings,
+    flags: &Flags,
+    material_database: &MaterialDatabase,
+) -> Result<HashMap<String, String>, String> {
+    match flags.get("use") {
+        Some(possible_flag_value) => match possible_flag_value {
+            Some(flag_value) if flag_value.to_lowercase() == "none" => Ok(HashMap::new()),
+            Some(flag_value) => match material_database.find_recipes(flag_value) {
+                Ok(recipes) => {
+                    let mut updated_settings = user_settings.clone();
+                    for recipe in recipes {
+                        updated_settings.choose_recipe(recipe, material_database);
+                    }
+                    Ok(updated_settings.recipe_choices)
+                }
+                Err(why) => Err(format!("Error: {}", why)),
+            },
+            None => Err("No value found for -use flag".to_string()),
+        },
+        None => Ok(user_settings.recipe_choices.clone()),
+    }
+}
+
+fn get_whole_number(user_settings: &UserSettings, flags: &Flags) -> Result<bool, String> {
+    match flags.get("w") {
+        Some(possible_flag_value) => match possible_flag_value {
+            Some(flag_value) => match &*flag_value.to_lowercase() {
+                "true" => Ok(true),
+                "false" => Ok(false),
+                _ => Err(format!("Error: Invalid whole flag *{}*", flag_value)),
+            },
+            None => Err("No value found for -w flag".to_string()),
+        },
+        None => Ok(user_settings.whole_numbers),
+    }
+}
+
+fn get_show_pollution(user_settings: &UserSettings, flags: &Flags) -> Result<bool, String> {
+    match flags.get("pollution") {
+        Some(possible_flag_value) => match possible_flag_value {
+            Some(flag_value) => match &*flag_value.to_lowercase() {
+                "true" => Ok(true),
+                "false" => Ok(false),
+                _ => Err(format!("Error: Invalid pollution flag *{}*", flag_value)),
+            },
+            None => Err("No value found for -pollution flag".to_string()),
+        },
+        None => Ok(user_settings.show_pollution),
+    }
+}
+
+fn get_ignore_furnace(user_settings: &UserSettings, flags: &Flags) -> Result<bool, String> {
+    match flags.get("ignore-furnace") {
+        Some(possible_flag_value) => match possible_flag_value {
+            Some(flag_value) => match &*flag_value.to_lowercase() {
+                "true" => Ok(true),
+                "false" => Ok(false),
+                _ => Err(format!("Invalid ignore furnace flag *{}*", flag_value)),
+            },
+            None => Err("Error: No value found for -ignore-furnace flag".to_string()),
+        },
+        None => Ok(user_settings.ignore_furnaces_for_ratio),
+    }
+}
+
+fn get_ignore_raw(user_settings: &UserSettings, flags: &Flags) -> Result<bool, String> {
+    match flags.get("ignore-raw") {
+        Some(possible_flag_value) => match possible_flag_value {
+            Some(flag_value) => match &*flag_value.to_lowercase() {
+                "true" => Ok(true),
+                "false" => Ok(false),
+                _ => Err(format!("Invalid ignore raw flag *{}*", flag_value)),
+            },
+            None => Err("Error: No value found for -ignore-raw flag".to_string()),
+        },
+        None => Ok(user_settings.ignore_raw_for_ratio),
+    }
+}
+
+fn get_module_target(
+    user_settings: &UserSettings,
+    flags: &Flags,
+    material_database: &MaterialDatabase,
+    guild_aliases: &HashMap<String, String>,
+) -> Result<Option<ModuleTarget>, String> {
+    let producer = match flags.get("producer") {
+        Some(Some(flag_value)) => match parse_producer_type(flag_value) {
+            Some(producer_type) => Some(ModuleTarget::Producer(producer_type)),
+            None => return Err(format!("Error: Invalid producer *{}*", flag_value)),
+        },
+        Some(None) => return Err("No value found for -producer flag".to_string()),
+        None => None,
+    };
+    let item = match flags.get("item") {
+        Some(Some(flag_value)) => {
+            let recipe = lookup_item_recipe(user_settings, flag_value, material_database, guild_aliases)?;
+            Some(ModuleTarget::Item(recipe.name.to_lowercase(), recipe.producer_type))
+        }
+        Some(None) => return Err("No value found for -item flag".to_string()),
+        None => None,
+    };
+    let configures_modules = flags.get("modules").is_some() || flags.get("beacons").is_some();
+    match (producer, item) {
+        (Some(_), Some(_)) => Err("Error: Use either -producer or -item, not both".to_string()),
+        (None, None) if configures_modules => {
+            Err("Error: -modules and -beacons need a -producer or -item".to_string())
+        }
+        (producer, item) => Ok(producer.or(item)),
+    }
+}
+
+// -item takes an item like every other command, by name, alias or close
+// spelling, and applies to the recipe making it. Recipes that aren't named
+// after an item, like advanced oil processing, can be given by name too.
+fn lookup_item_recipe<'a>(
+    user_settings: &UserSettings,
+    item_name: &str,
+    material_database: &'a MaterialDatabase,
+    guild_aliases: &HashMap<String, String>,
+) -> Result<&'a Recipe, String> {
+    let material = match material_database.resolve_material(item_name, guild_aliases) {
+        Some(material) => material,
+        None => match material_database.find_recipe(item_name) {
+            Some(recipe) => return Ok(recipe),
+            None => material_database
+                .lookup_material(item_name, guild_aliases)
+                .map_err(|why| format!("Error: {}", why))?,
+        },
+    };
+    material_database
+        .producing_recipe(material, user_settings)
+        .ok_or_else(|| format!("Error: *{}* is a raw material, so no machine makes it", material.name))
+}
+
+fn get_module_configurations(
+    user_settings: &UserSettings,
+    flags: &Flags,
+    module_target: &Option<ModuleTarget>,
+) -> Result<HashMap<ProducerType, ModuleConfiguration>, String> {
+    let mut module_configurations = user_settings.module_configurations.clone();
+    if let Some(ModuleTarget::Producer(producer_type)) = module_target {
+        let existing = module_configurations
+            .get(producer_type)
+            .cloned()
+            .unwrap_or_default();
+        match get_module_configuration(existing, flags, producer_type)? {
+            Some(module_configuration) => {
+                module_configurations.insert(*producer_type, module_configuration)
+            }
+            None => module_configurations.remove(producer_type),
+        };
+    }
+    Ok(module_configurations)
+}
+
+fn get_module_overrides(
+    user_settings: &UserSettings,
+    flags: &Flags,
+    module_target: &Option<ModuleTarget>,
+) -> Result<HashMap<String, ModuleConfiguration>, String> {
+    let mut module_overrides = user_settings.module_overrides.clone();
+    if let Some(ModuleTarget::Item(item_name, producer_type)) = module_target {
+        if flags.get("modules").is_none() && flags.get("beacons").is_none() {
+            return Ok(module_overrides);
+        }
+        let existing = module_overrides
+            .get(item_name)
+            .or_else(|| user_settings.module_configurations.get(producer_type))
+            .cloned()
+            .unwrap_or_default();
+        match get_module_configuration(existing, flags, producer_type)? {
+            Some(module_configuration) => {
+                module_overrides.insert(item_name.clone(), module_configuration)
+            }
+            None => module_overrides.remove(item_name),
+        };
+    }
+    Ok(module_overrides)
+}
+
+fn get_level_overrides(
+    user_settings: &UserSettings,
+    flags: &Flags,
+    module_target: &Option<ModuleTarget>,
+) -> Result<HashMap<String, LevelOverride>, String> {
+    let mut level_overrides = user_settings.level_overrides.clone();
+    if let Some(ModuleTarget::Item(item_name, producer_type)) = module_target {
+        let mut level_override = level_overrides.get(item_name).cloned().unwrap_or_default();
+        match (flags.get("a"), producer_type) {
+            (Some(Some(flag_value)), ProducerType::AssemblingMachine) => {
+                level_override.assembling_machine_level = match &*flag_value.to_lowercase() {
+                    "default" => None,
+                    _ => Some(parse_assembling_machine_level(flag_value)?),
+                }
+            }
+            (Some(Some(_)), _) => {
+                return Err(format!("Error: *{}* is not made in an assembling machine", item_name))
+            }
+            (Some(None), _) => return Err("No value found for -a flag".to_string()),
+            (None, _) => {}
+        }
+        match (flags.get("f"), producer_type) {
+            (Some(Some(flag_value)), ProducerType::Furnace) => {
+                level_override.furnace_level = match &*flag_value.to_lowercase() {
+                    "default" => None,
+                    _ => Some(parse_furnace_level(flag_value)?),
+                }
+            }
+            (Some(Some(_)), _) => {
+                return Err(format!("Error: *{}* is not made in a furnace", item_name))
+            }
+            (Some(None), _) => return Err("No value found for -f flag".to_string()),
+            (None, _) => {}
+        }
+        match level_override.assembling_machine_level.is_none() && level_override.furnace_level.is_none() {
+            true => level_overrides.remove(item_name),
+            false => level_overrides.insert(item_name.clone(), level_override),
+        };
+    }
+    Ok(level_overrides)
+}
+
+// Returns None when the configuration should go back to its default.
+fn get_module_configuration(
+    existing: ModuleConfiguration,
+    flags: &Flags,
+    producer_type: &ProducerType,
+) -> Result<Option<ModuleConfiguration>, String> {
+    let modules = match flags.get("modules") {
+        Some(Some(flag_value)) => match &*flag_value.to_lowercase() {
+            "default" => return Ok(None),
+            "none" => Vec::new(),
+            flag_value => parse_modules(flag_value)?,
+        },
+        Some(None) => return Err("No value found for -modules flag".to_string()),
+        None => existing.modules,
+    };
+    let beacons = match flags.get("beacons") {
+        Some(Some(flag_value)) => match flag_value.parse::<u32>() {
+            Ok(beacons) => beacons,
+            Err(_) => return Err(format!("Error: Invalid beacon count *{}*", flag_value)),
+        },
+        Some(None) => return Err("No value found for -beacons flag".to_string()),
+        None => existing.beacons,
+    };
+    let maximum_slots = module_slots(
+        producer_type,
+        &AssemblingMachineLevel::Three,
+        &FurnaceLevel::Electric,
+    );
+    if modules.len() > maximum_slots {
+        return Err(format!(
+            "Error: At most {} modules fit in this machine",
+            maximum_slots
+        ));
+    }
+    Ok(Some(ModuleConfiguration::new(modules, beacons)))
+}
+
+fn parse_modules(flag_value: &str) -> Result<Vec<Module>, String> {
+    flag_value
+        .split(',')
+        .map(|module_name| match module_name.trim() {
+            "s1" | "speed1" => Ok(Module::Speed1),
+            "s2" | "speed2" => Ok(Module::Speed2),
+            "s3" | "speed3" => Ok(Module::Speed3),
+            "p1" | "prod1" | "productivity1" => Ok(Module::Productivity1),
+            "p2" | "prod2" | "productivity2" => Ok(Module::Productivity2),
+            "p3" | "prod3" | "productivity3" => Ok(Module::Productivity3),
+            "e1" | "efficiency1" => Ok(Module::Efficiency1),
+            "e2" | "efficiency2" => Ok(Module::Efficiency2),
+            "e3" | "efficiency3" => Ok(Module::Efficiency3),
+            _ => Err(format!("Error: Invalid module *{}*", module_name)),
+        })
+        .collect()
+}
+
+fn parse_producer_type(flag_value: &str) -> Option<ProducerType> {
+    match &*flag_value.to_lowercase() {
+        "assembling-machine" | "assembler" | "am" => Some(ProducerType::AssemblingMachine),
+        "furnace" | "f" => Some(ProducerType::Furnace),
+        "chemical-plant" | "cp" => Some(ProducerType::ChemicalPlant),
+        "oil-refinery" | "refinery" | "or" => Some(ProducerType::OilRefinery),
+        "centrifuge" | "c" => Some(ProducerType::Centrifuge),
+        "rocket-silo" | "rs" => Some(ProducerType::RocketSilo),
+        _ => None,
+    }
+}
@@ -0,0 +1,197 @@
+use crate::user_settings::{ResolvedSettings, SettingsLevel, UserSettings, UserSettingsDatabase};
+use serenity::framework::standard::CommandResult;
+use serenity::framework::standard::macros::{command};
+use serenity::{builder::CreateEmbed, model::channel::Message, prelude::*};
+use tracing::error;
+
+#[command]
+#[help_available]
+#[description("Gives the exact amount of machines needed to create an item.")]
+#[usage("!recipe *item name* or !recipe *item name* -a *amount per second*")]
+#[example("!recipe logistic science pack*")]
+#[example("!recipe logistic science pack -a 5*")]
+#[aliases("user-settings")]
+pub async fn user_settings(context: &Context, message: &Message) -> CommandResult {
+    let data = context.data.read().await;
+    let user_settings_database = data.get::<UserSettingsDatabase>().unwrap();
+    let resolved_settings = user_settings_database.resolve(message);
+    let sent_message = message.channel_id.send_message(&context.http, |m| {
+        m.content(format!("Settings for user {}:", &message.author.name))
+            .embed(|e| add_settings_fields(e, &resolved_settings))
+    });
+
+    if let Err(why) = sent_message.await {
+        error!("Error sending message: {:?}", why);
+    }
+    Ok(())
+}
+
+// Lists every effective setting along with the level it comes from.
+pub fn add_settings_fields<'a>(
+    embed: &'a mut CreateEmbed,
+    resolved_settings: &ResolvedSettings,
+) -> &'a mut CreateEmbed {
+    let user_settings = &resolved_settings.settings;
+    let fields: Vec<(&str, String, &[&str])> = vec![
+        (
+            "Assembling machine level:",
+            user_settings.assembling_machine_level.to_string(),
+            &["assembling_machine_level"],
+        ),
+        (
+            "Furnace level:",
+            user_settings.furnace_level.to_string(),
+            &["furnace_level"],
+        ),
+        (
+            "Mining drill:",
+            user_settings.mining_drill_level.to_string(),
+            &["mining_drill_level"],
+        ),
+        (
+            "Mining productivity level:",
+            user_settings.mining_productivity_level.to_string(),
+            &["mining_productivity_level"],
+        ),
+        (
+            "Pumpjack yield:",
+            format!("{}%", user_settings.pumpjack_yield),
+            &["pumpjack_yield"],
+        ),
+        (
+            "Belt:",
+            user_settings.belt_tier.to_string(),
+            &["belt_tier"],
+        ),
+        (
+            "Rates shown per:",
+            user_settings.display_unit.to_string(),
+            &["display_unit"],
+        ),
+        (
+            "Tree depth:",
+            user_settings.tree_depth.to_string(),
+            &["tree_depth"],
+        ),
+        (
+            "Whole numbers in ratios:",
+            convert_bool_to_capital_string(user_settings.whole_numbers),
+            &["whole_numbers"],
+        ),
+        (
+            "Show pollution:",
+            convert_bool_to_capital_string(user_settings.show_pollution),
+            &["show_pollution"],
+        ),
+        (
+            "Ignore furnaces for ratios:",
+            convert_bool_to_capital_string(
+                user_settings.ignore_furnaces_for_ratio,
+            ),
+            &["ignore_furnaces_for_ratio"],
+        ),
+        (
+            "Ignore raw materials for ratios:",
+            convert_bool_to_capital_string(user_settings.ignore_raw_for_ratio),
+            &["ignore_raw_for_ratio"],
+        ),
+        (
+            "Imported items:",
+            match user_settings.imports.is_empty() {
+                true => "None".to_string(),
+                false => user_settings.imports.join(", "),
+            },
+            &["imports"],
+        ),
+        (
+            "Machine overrides:",
+            describe_level_overrides(user_settings),
+            &["level_overrides"],
+        ),
+        (
+            "Recipe choices:",
+            describe_recipe_choices(user_settings),
+            &["recipe_choices"],
+        ),
+        (
+            "Modules:",
+            describe_module_configurations(user_settings),
+            &["module_configurations", "module_overrides"],
+        ),
+    ];
+    embed.fields(fields.into_iter().map(|(title, value, field_names)| {
+        (title, format!("{} *({})*", value, describe_sources(resolved_settings, field_names)), false)
+    }))
+}
+
+fn describe_sources(resolved_settings: &ResolvedSettings, field_names: &[&str]) -> String {
+    let mut sources: Vec<SettingsLevel> = field_names
+        .iter()
+        .map(|field_name| resolved_settings.source(field_name))
+        .collect();
+    sources.sort();
+    sources.dedup();
+    sources.reverse();
+    sources
+        .iter()
+        .map(|source| source.to_string())
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+fn describe_level_overrides(user_settings: &UserSettings) -> String {
+    let mut description_vec: Vec<String> = user_settings
+        .level_overrides
+        .iter()
+        .map(|(item_name, level_override)| format!("*{}*: {}", item_name, level_override))
+        .collect();
+    description_vec.sort();
+    match description_vec.is_empty() {
+        true => "None".to_string(),
+        false => description_vec.join("\n"),
+    }
+}
+
+fn describe_recipe_choices(user_settings: &UserSettings) -> String {
+    let mut description_vec: Vec<String> = user_settings
+        .recipe_choices
+        .iter()
+        .map(|(item_name, recipe_name)| format!("*{}*: {}", item_name, recipe_name))
+        .collect();
+    description_vec.sort();
+    match description_vec.is_empty() {
+        true => "None".to_string(),
+        false => description_vec.join("\n"),
+    }
+}
+
+fn describe_module_configurations(user_settings: &UserSettings) -> String {
+    let mut description_vec: Vec<String> = user_settings
+        .module_configurations
+        .iter()
+        .map(|(producer_type, module_configuration)| {
+            format!("{}: {}", producer_type, module_configuration)
+        })
+        .collect();
+    description_vec.sort();
+    let mut overrides: Vec<String> = user_settings
+        .module_overrides
+        .iter()
+        .map(|(item_name, module_configuration)| {
+            format!("*{}*: {}", item_name, module_configuration)
+        })
+        .collect();
+    overrides.sort();
+    description_vec.extend(overrides);
+    match description_vec.is_empty() {
+        true => "None".to_string(),
+        false => description_vec.join("\n"),
+    }
+}
+
+fn convert_bool_to_capital_string(b: bool) -> String {
+    match b {
+        true => "True".to_string(),
+        false => "False".to_string(),
+    }
+}
@@ -0,0 +1,144 @@
+extern crate serenity;
+
+use crate::config::Config;
+use crate::guild_aliases::GuildAliasDatabase;
+use crate::handlers::alias_command_handler::{ADD_ALIAS_COMMAND, ALIASES_COMMAND, REMOVE_ALIAS_COMMAND};
+use crate::handlers::claim_settings_command_handler::CLAIM_SETTINGS_COMMAND;
+use crate::handlers::default_settings_command_handler::{CHANNEL_SETTINGS_COMMAND, SERVER_SETTINGS_COMMAND};
+use crate::handlers::help_command_handler::HELP_COMMAND;
+use crate::handlers::info_command_handler::INFO_COMMAND;
+use crate::handlers::profile_command_handler::PROFILE_COMMAND;
+use crate::handlers::recipe_command_handler::RECIPE_COMMAND;
+use crate::material_database_key::MaterialDatabaseKey;
+use crate::handlers::update_settings_command_handler::UPDATE_SETTINGS_COMMAND;
+use crate::handlers::user_settings_command_handler::USER_SETTINGS_COMMAND;
+use crate::user_settings::UserSettingsDatabase;
+use logistic_robot_core::MaterialDatabase;
+use serenity::framework::standard::macros::{group, hook};
+use serenity::framework::standard::{DispatchError, StandardFramework};
+use serenity::{model::channel::Message, prelude::Context};
+use std::sync::Arc;
+use tracing::error;
+use tracing_subscriber::EnvFilter;
+
+mod config;
+mod guild_aliases;
+mod handlers;
+mod material_database_key;
+mod profiles;
+mod storage;
+mod user_settings;
+
+#[group]
+#[commands(
+    recipe, info, user_settings, update_settings, profile, server_settings, channel_settings, aliases,
+    add_alias, remove_alias, claim_settings, help
+)]
+struct General;
+
+#[hook]
+async fn dispatch_error(context: &Context, message: &Message, error: DispatchError) {
+    let response = match error {
+        DispatchError::LackingPermissions(_) => "Only server admins can use this command.",
+        DispatchError::OnlyForGuilds => "This command can only be used in a server.",
+        _ => return,
+    };
+    if let Err(why) = message.channel_id.say(&context.http, response).await {
+        error!("Unable to send message: {}", why);
+    }
+}
+
+#[tokio::main]
+async fn main() {
+    let config = match Config::load() {
+        Ok(config) => config,
+        Err(why) => {
+            eprintln!("Invalid configuration:");
+            for problem in why.problems {
+                eprintln!("  {}", problem);
+            }
+            std::process::exit(1);
+        }
+    };
+    tracing_subscriber::fmt()
+        .with_env_filter(EnvFilter::new(&config.log_level))
+        .init();
+
+    let framework = StandardFramework::new()
+        .configure(|c| {
+            c.prefix(&config.prefix).owners(config.owners.clone())
+        })
+        .on_dispatch_error(dispatch_error)
+        .group(&GENERAL_GROUP);
+    let mut client = match serenity::Client::builder(&config.token).framework(framework).await {
+        Ok(client) => client,
+        Err(why) => {
+            error!("Unable to create client: {}", why);
+            std::process::exit(1);
+        }
+    };
+    {
+        let mut data = client.data.write().await;
+
+        // Without a configured path the materials.json built into the core crate is used.
+        let (file_name, possible_material_database) = match &config.materials_path {
+            Some(materials_path) => match std::fs::read_to_string(materials_path) {
+                Ok(json_file_contents) => (
+                    materials_path.display().to_string(),
+                    MaterialDatabase::new(json_file_contents),
+                ),
+                Err(why) => {
+                    error!("Unable to read {}: {}", materials_path.display(), why);
+                    std::process::exit(1);
+                }
+            },
+            None => ("built-in materials.json".to_string(), MaterialDatabase::built_in()),
+        };
+
+        let material_database = match possible_material_database {
+            Ok(material_database) => material_database,
+            Err(why) => {
+                error!("Unable to load {}:", file_name);
+                for problem in why.problems {
+                    error!("  {}", problem);
+                }
+                std::process::exit(1);
+            }
+        };
+
+        let databases = storage::open_storage(&config.settings_storage, &config.settings_path).and_then(|storage| {
+            Ok((
+                UserSettingsDatabase::load(storage.clone())?,
+                GuildAliasDatabase::load(storage)?,
+            ))
+        });
+        let (user_settings_database, guild_alias_database) = match databases {
+            Ok(databases) => databases,
+            Err(why) => {
+                error!("Unable to load user settings from {}: {}", config.settings_path.display(), why);
+                std::process::exit(1);
+            }
+        };
+
+        data.insert::<UserSettingsDatabase>(user_settings_database);
+        data.insert::<GuildAliasDatabase>(guild_alias_database);
+        data.insert::<MaterialDatabaseKey>(Arc::new(material_database));
+        data.insert::<Config>(Arc::new(config));
+    }
+
+    let shard_manager = client.shard_manager.clone();
+    tokio::spawn(async move {
+        if tokio::signal::ctrl_c().await.is_ok() {
+            shard_manager.lock().await.shutdown_all().await;
+        }
+    });
+
+    if let Err(why) = client.start().await {
+        error!("Client error: {:?}", why);
+    }
+
+    let data = client.data.read().await;
+    if let Err(why) = data.get::<UserSettingsDatabase>().unwrap().flush() {
+        error!("Unable to save user settings: {}", why);
+    }
+}
@@ -0,0 +1,10 @@
+use logistic_robot_core::MaterialDatabase;
+use serenity::prelude::TypeMapKey;
+use std::sync::Arc;
+
+// The core crate knows nothing of serenity, so the client's data keeps the
+// shared database under this key rather than under its own type.
+pub struct MaterialDatabaseKey;
+impl TypeMapKey for MaterialDatabaseKey {
+    type Value = Arc<MaterialDatabase>;
+}
@@ -0,0 +1,95 @@
+use crate::user_settings::UserSettings;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+pub const MAX_PROFILES: usize = 25;
+pub const MAX_PROFILE_NAME_LENGTH: usize = 32;
+
+// A user's saved setups, each a snapshot of the settings they had in effect
+// when it was saved. Names are matched ignoring case.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UserProfiles {
+    pub active: Option<String>,
+    pub profiles: BTreeMap<String, UserSettings>,
+}
+impl UserProfiles {
+    pub fn get(&self, name: &str) -> Option<(&String, &UserSettings)> {
+        self.profiles
+            .iter()
+            .find(|(profile_name, _settings)| profile_name.to_lowercase() == name.to_lowercase())
+    }
+
+    pub fn insert(&mut self, name: &str, user_settings: UserSettings) -> Result<(), String> {
+        if name.chars().count() > MAX_PROFILE_NAME_LENGTH {
+            return Err(format!(
+                "Error: Profile names can be at most {} characters long",
+                MAX_PROFILE_NAME_LENGTH
+            ));
+        }
+        let existing_name = self.get(name).map(|(profile_name, _settings)| profile_name.clone());
+        if existing_name.is_none() && self.profiles.len() >= MAX_PROFILES {
+            return Err(format!(
+                "Error: You can have at most {} profiles, delete one first",
+                MAX_PROFILES
+            ));
+        }
+        let name = existing_name.unwrap_or_else(|| name.to_string());
+        self.profiles.insert(name.clone(), user_settings);
+        self.active = Some(name);
+        Ok(())
+    }
+
+    pub fn remove(&mut self, name: &str) -> Option<String> {
+        let (profile_name, _settings) = self.get(name)?;
+        let profile_name = profile_name.clone();
+        self.profiles.remove(&profile_name);
+        if self.active.as_ref() == Some(&profile_name) {
+            self.active = None;
+        }
+        Some(profile_name)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.profiles.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn replaces_profiles_ignoring_case_and_limits_them() {
+        let mut profiles = UserProfiles::default();
+        profiles.insert("Megabase", UserSettings::default()).unwrap();
+        profiles.insert("megabase", UserSettings { whole_numbers: true, ..UserSettings::default() }).unwrap();
+        assert_eq!(profiles.profiles.keys().collect::<Vec<&String>>(), vec!["Megabase"]);
+        assert!(profiles.get("MEGABASE").unwrap().1.whole_numbers);
+        assert_eq!(profiles.active.as_deref(), Some("Megabase"));
+
+        assert!(profiles.insert(&"x".repeat(MAX_PROFILE_NAME_LENGTH + 1), UserSettings::default()).is_err());
+        for index in 1..MAX_PROFILES {
+            profiles.insert(&format!("Profile {}", index), UserSettings::default()).unwrap();
+        }
+        assert_eq!(
+            profiles.insert("One too many", UserSettings::default()),
+            Err(format!("Error: You can have at most {} profiles, delete one first", MAX_PROFILES))
+        );
+        profiles.insert("megabase", UserSettings::default()).unwrap();
+        assert_eq!(profiles.profiles.len(), MAX_PROFILES);
+    }
+
+    #[test]
+    fn removing_the_active_profile_clears_it() {
+        let mut profiles = UserProfiles::default();
+        profiles.insert("Early", UserSettings::default()).unwrap();
+        profiles.insert("Megabase", UserSettings::default()).unwrap();
+        assert_eq!(profiles.remove("early"), Some("Early".to_string()));
+        assert_eq!(profiles.active.as_deref(), Some("Megabase"));
+        assert_eq!(profiles.remove("megabase"), Some("Megabase".to_string()));
+        assert_eq!(profiles.active, None);
+        assert_eq!(profiles.remove("megabase"), None);
+        assert!(profiles.is_empty());
+    }
+}
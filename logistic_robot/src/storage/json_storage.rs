@@ -0,0 +1,60 @@
+use crate::storage::{StorageError, UserSettingsStorage};
+use crate::user_settings::PartialSettings;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+// Keeps every user's settings in one json file, rewritten in full on each save.
+pub struct JsonStorage {
+    path: PathBuf,
+    settings_hash_map: Mutex<HashMap<String, PartialSettings>>,
+}
+impl JsonStorage {
+    pub fn new(path: &Path) -> Self {
+        JsonStorage {
+            path: path.to_path_buf(),
+            settings_hash_map: Mutex::new(HashMap::new()),
+        }
+    }
+
+    // Writes to a temporary file next to the real one and renames it over the
+    // top, so a crash mid-write never leaves a truncated file behind.
+    fn write(&self, settings_hash_map: &HashMap<String, PartialSettings>) -> Result<(), StorageError> {
+        let mut temporary_path = self.path.clone().into_os_string();
+        temporary_path.push(".tmp");
+        let mut file = File::create(&temporary_path)?;
+        file.write_all(serde_json::to_string_pretty(settings_hash_map)?.as_bytes())?;
+        file.sync_all()?;
+        std::fs::rename(&temporary_path, &self.path)?;
+        Ok(())
+    }
+}
+impl UserSettingsStorage for JsonStorage {
+    fn load(&self) -> Result<HashMap<String, PartialSettings>, StorageError> {
+        let settings_hash_map: HashMap<String, PartialSettings> = match std::fs::read_to_string(&self.path) {
+            Ok(contents) => serde_json::from_str(&contents)?,
+            Err(why) if why.kind() == std::io::ErrorKind::NotFound => HashMap::new(),
+            Err(why) => return Err(why.into()),
+        };
+        *self.settings_hash_map.lock().unwrap() = settings_hash_map.clone();
+        Ok(settings_hash_map)
+    }
+
+    fn save(&self, key: &str, settings: &PartialSettings) -> Result<(), StorageError> {
+        let mut settings_hash_map = self.settings_hash_map.lock().unwrap();
+        settings_hash_map.insert(key.to_string(), settings.clone());
+        self.write(&settings_hash_map)
+    }
+
+    fn remove(&self, key: &str) -> Result<(), StorageError> {
+        let mut settings_hash_map = self.settings_hash_map.lock().unwrap();
+        settings_hash_map.remove(key);
+        self.write(&settings_hash_map)
+    }
+
+    fn flush(&self) -> Result<(), StorageError> {
+        self.write(&self.settings_hash_map.lock().unwrap())
+    }
+}
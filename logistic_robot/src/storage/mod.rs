@@ -0,0 +1,134 @@
+use crate::user_settings::PartialSettings;
+use core::fmt::Error;
+use core::fmt::Formatter;
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::path::Path;
+use std::sync::Arc;
+
+pub mod json_storage;
+pub mod sqlite_storage;
+
+#[derive(Debug)]
+pub enum StorageError {
+    Io(std::io::Error),
+    Json(serde_json::Error),
+    Sqlite(rusqlite::Error),
+    UnknownBackend(String),
+}
+impl Display for StorageError {
+    fn fmt(&self, formatter: &mut Formatter) -> Result<(), Error> {
+        match self {
+            StorageError::Io(why) => write!(formatter, "I/O error: {}", why),
+            StorageError::Json(why) => write!(formatter, "Invalid settings json: {}", why),
+            StorageError::Sqlite(why) => write!(formatter, "SQLite error: {}", why),
+            StorageError::UnknownBackend(backend) => write!(
+                formatter,
+                "Unknown settings storage {}, expected json or sqlite",
+                backend
+            ),
+        }
+    }
+}
+impl From<std::io::Error> for StorageError {
+    fn from(why: std::io::Error) -> Self {
+        StorageError::Io(why)
+    }
+}
+impl From<serde_json::Error> for StorageError {
+    fn from(why: serde_json::Error) -> Self {
+        StorageError::Json(why)
+    }
+}
+impl From<rusqlite::Error> for StorageError {
+    fn from(why: rusqlite::Error) -> Self {
+        StorageError::Sqlite(why)
+    }
+}
+
+// Where UserSettingsDatabase keeps settings between restarts, under the keys it
+// chooses for users, channels and servers, and GuildAliasDatabase keeps server
+// aliases under "aliases:<guild id>". Both share one storage. Every update is
+// saved straight away; flush is called once more on shutdown.
+pub trait UserSettingsStorage: Send + Sync {
+    fn load(&self) -> Result<HashMap<String, PartialSettings>, StorageError>;
+    fn save(&self, key: &str, settings: &PartialSettings) -> Result<(), StorageError>;
+    fn remove(&self, key: &str) -> Result<(), StorageError>;
+    fn flush(&self) -> Result<(), StorageError>;
+}
+
+pub fn open_storage(backend: &str, path: &Path) -> Result<Arc<dyn UserSettingsStorage>, StorageError> {
+    match backend {
+        "json" => Ok(Arc::new(json_storage::JsonStorage::new(path))),
+        "sqlite" => Ok(Arc::new(sqlite_storage::SqliteStorage::open(path)?)),
+        _ => Err(StorageError::UnknownBackend(backend.to_string())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::path::PathBuf;
+
+    fn temporary_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("logistic_robot_{}_{}", std::process::id(), name))
+    }
+
+    fn settings(value: serde_json::Value) -> PartialSettings {
+        value.as_object().unwrap().clone()
+    }
+
+    // Saves, overwrites and removes through one storage, then checks a fresh
+    // one opened on the same path sees the result.
+    fn round_trip(backend: &str, path: &Path) {
+        let storage = open_storage(backend, path).unwrap();
+        assert!(storage.load().unwrap().is_empty());
+        storage.save("1", &settings(json!({"furnace_level": "Steel"}))).unwrap();
+        storage.save("1", &settings(json!({"furnace_level": "Electric"}))).unwrap();
+        storage.save("guild:2", &settings(json!({"assembling_machine_level": 3}))).unwrap();
+        storage.save("3", &settings(json!({}))).unwrap();
+        storage.remove("3").unwrap();
+        storage.flush().unwrap();
+
+        let loaded = open_storage(backend, path).unwrap().load().unwrap();
+        let mut expected = HashMap::new();
+        expected.insert("1".to_string(), settings(json!({"furnace_level": "Electric"})));
+        expected.insert("guild:2".to_string(), settings(json!({"assembling_machine_level": 3})));
+        assert_eq!(loaded, expected);
+    }
+
+    #[test]
+    fn round_trips_json_storage() {
+        let path = temporary_path("settings.json");
+        round_trip("json", &path);
+        let mut temporary_file = path.clone().into_os_string();
+        temporary_file.push(".tmp");
+        assert!(!Path::new(&temporary_file).exists());
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn round_trips_sqlite_storage() {
+        let path = temporary_path("settings.sqlite");
+        round_trip("sqlite", &path);
+        let journal_mode: String = rusqlite::Connection::open(&path)
+            .unwrap()
+            .query_row("PRAGMA journal_mode", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(journal_mode, "wal");
+        for suffix in &["", "-wal", "-shm"] {
+            let mut file = path.clone().into_os_string();
+            file.push(suffix);
+            std::fs::remove_file(&file).ok();
+        }
+    }
+
+    #[test]
+    fn rejects_unknown_backends() {
+        assert!(matches!(
+            open_storage("yaml", &temporary_path("settings.yaml")),
+            Err(StorageError::UnknownBackend(_))
+        ));
+    }
+}
@@ -0,0 +1,64 @@
+use crate::storage::{StorageError, UserSettingsStorage};
+use crate::user_settings::PartialSettings;
+use rusqlite::{params, Connection};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Mutex;
+
+// One row per user, holding their settings as json so new fields don't need
+// a schema change.
+pub struct SqliteStorage {
+    connection: Mutex<Connection>,
+}
+impl SqliteStorage {
+    pub fn open(path: &Path) -> Result<Self, StorageError> {
+        let connection = Connection::open(path)?;
+        connection.pragma_update(None, "journal_mode", "WAL")?;
+        connection.execute(
+            "CREATE TABLE IF NOT EXISTS user_settings (name TEXT PRIMARY KEY, settings TEXT NOT NULL)",
+            [],
+        )?;
+        Ok(SqliteStorage { connection: Mutex::new(connection) })
+    }
+}
+impl UserSettingsStorage for SqliteStorage {
+    fn load(&self) -> Result<HashMap<String, PartialSettings>, StorageError> {
+        let connection = self.connection.lock().unwrap();
+        let mut statement = connection.prepare("SELECT name, settings FROM user_settings")?;
+        let rows = statement.query_map([], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+        })?;
+        let mut settings_hash_map: HashMap<String, PartialSettings> = HashMap::new();
+        for row in rows {
+            let (key, settings) = row?;
+            settings_hash_map.insert(key, serde_json::from_str(&settings)?);
+        }
+        Ok(settings_hash_map)
+    }
+
+    fn save(&self, key: &str, settings: &PartialSettings) -> Result<(), StorageError> {
+        self.connection.lock().unwrap().execute(
+            "INSERT INTO user_settings (name, settings) VALUES (?1, ?2)
+             ON CONFLICT(name) DO UPDATE SET settings = excluded.settings",
+            params![key, serde_json::to_string(settings)?],
+        )?;
+        Ok(())
+    }
+
+    fn remove(&self, key: &str) -> Result<(), StorageError> {
+        self.connection
+            .lock()
+            .unwrap()
+            .execute("DELETE FROM user_settings WHERE name = ?1", params![key])?;
+        Ok(())
+    }
+
+    // Moves everything from the write-ahead log into the database file itself.
+    fn flush(&self) -> Result<(), StorageError> {
+        self.connection
+            .lock()
+            .unwrap()
+            .query_row("PRAGMA wal_checkpoint(TRUNCATE)", [], |_row| Ok(()))?;
+        Ok(())
+    }
+}
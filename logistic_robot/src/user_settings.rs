@@ -0,0 +1,521 @@
+use crate::guild_aliases::ALIASES_KEY_PREFIX;
+use crate::profiles::UserProfiles;
+use crate::storage::{StorageError, UserSettingsStorage};
+use core::fmt::Error;
+use core::fmt::Formatter;
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::sync::Arc;
+use serde_json::Value;
+use serenity::model::{channel::Message, id::UserId, user::User};
+use serenity::prelude::*;
+use tracing::{error, warn};
+
+pub use logistic_robot_core::user_settings::{LevelOverride, UserSettings, USER_SETTINGS_VERSION};
+
+// Where a setting's effective value comes from. Each level overrides the ones
+// before it.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum SettingsLevel {
+    Default,
+    Server,
+    Channel,
+    User,
+}
+impl Display for SettingsLevel {
+    fn fmt(&self, formatter: &mut Formatter) -> Result<(), Error> {
+        match self {
+            SettingsLevel::Default => write!(formatter, "default"),
+            SettingsLevel::Server => write!(formatter, "server"),
+            SettingsLevel::Channel => write!(formatter, "channel"),
+            SettingsLevel::User => write!(formatter, "you"),
+        }
+    }
+}
+
+// Only the fields a server, channel or user has set themselves, stored the way
+// UserSettings serializes them.
+pub type PartialSettings = serde_json::Map<String, Value>;
+
+pub struct ResolvedSettings {
+    pub settings: UserSettings,
+    sources: HashMap<String, SettingsLevel>,
+}
+impl ResolvedSettings {
+    // Takes the serialized field name, e.g. "assembling_machine_level".
+    pub fn source(&self, field_name: &str) -> SettingsLevel {
+        self.sources
+            .get(field_name)
+            .copied()
+            .unwrap_or(SettingsLevel::Default)
+    }
+}
+
+// Why settings stored under a user name couldn't be moved to an id.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum LegacySettingsError {
+    NotFound(String),
+    Ambiguous(String, Vec<String>),
+}
+impl Display for LegacySettingsError {
+    fn fmt(&self, formatter: &mut Formatter) -> Result<(), Error> {
+        match self {
+            LegacySettingsError::NotFound(name) => {
+                write!(formatter, "No old settings are saved under the name *{}*", name)
+            }
+            LegacySettingsError::Ambiguous(name, legacy_names) => write!(
+                formatter,
+                "Old settings are saved under more than one name like *{}*: {}. Ask the bot owner to move yours",
+                name,
+                legacy_names
+                    .iter()
+                    .map(|legacy_name| format!("*{}*", legacy_name))
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            ),
+        }
+    }
+}
+
+// Server, channel and user settings are stored under "guild:<id>",
+// "channel:<id>" and the plain user id, and saved profiles under
+// "profiles:<user id>". Any other key is a user name from before settings were
+// keyed by id. Display names aren't unique, so those are never read; they are
+// only moved to an id when claimed with !claim-settings.
+pub struct UserSettingsDatabase {
+    settings_hash_map: HashMap<String, PartialSettings>,
+    legacy_settings_hash_map: HashMap<String, PartialSettings>,
+    profiles_hash_map: HashMap<UserId, UserProfiles>,
+    storage: Arc<dyn UserSettingsStorage>
+}
+impl TypeMapKey for UserSettingsDatabase {
+    type Value = UserSettingsDatabase;
+}
+impl UserSettingsDatabase {
+    pub fn load(storage: Arc<dyn UserSettingsStorage>) -> Result<Self, StorageError> {
+        let mut settings_hash_map = HashMap::new();
+        let mut legacy_settings_hash_map = HashMap::new();
+        let mut profiles_hash_map = HashMap::new();
+        for (key, settings) in storage.load()? {
+            if let Some(user_id) = key.strip_prefix("profiles:") {
+                if let Ok(user_id) = user_id.parse::<u64>() {
+                    match serde_json::from_value(Value::Object(settings)) {
+                        Ok(profiles) => {
+                            profiles_hash_map.insert(UserId(user_id), profiles);
+                        }
+                        Err(why) => warn!("Skipping unreadable profiles of {}: {}", user_id, why),
+                    }
+                }
+                continue;
+            }
+            if key.starts_with(ALIASES_KEY_PREFIX) {
+                continue;
+            }
+            let settings = upgrade_partial_settings(settings);
+            match key.parse::<u64>().is_ok() || key.contains(':') {
+                true => settings_hash_map.insert(key, settings),
+                false => legacy_settings_hash_map.insert(key, settings),
+            };
+        }
+        if !legacy_settings_hash_map.is_empty() {
+            warn!(
+                "{} settings are still stored under user names, until claimed with !claim-settings",
+                legacy_settings_hash_map.len()
+            );
+        }
+        Ok(UserSettingsDatabase {
+            settings_hash_map,
+            legacy_settings_hash_map,
+            profiles_hash_map,
+            storage,
+        })
+    }
+
+    // The settings that apply to a message's author in its channel.
+    pub fn resolve(&self, message: &Message) -> ResolvedSettings {
+        self.resolve_up_to(message, SettingsLevel::User)
+    }
+
+    // The settings someone would get from the given level and those below it,
+    // e.g. a channel's defaults when a user hasn't changed anything.
+    pub fn resolve_up_to(&self, message: &Message, level: SettingsLevel) -> ResolvedSettings {
+        let mut values = match serde_json::to_value(UserSettings::default()) {
+            Ok(Value::Object(values)) => values,
+            _ => PartialSettings::new(),
+        };
+        let mut sources = HashMap::new();
+        for (layer_level, layer) in self.layers(message, level) {
+            for (field_name, value) in layer {
+                if field_name != "version" {
+                    values.insert(field_name.clone(), value.clone());
+                    sources.insert(field_name.clone(), layer_level);
+                }
+            }
+        }
+        let settings = serde_json::from_value(Value::Object(values)).unwrap_or_else(|why| {
+            warn!("Unable to read stored settings, using defaults: {}", why);
+            UserSettings::default()
+        });
+        ResolvedSettings { settings, sources }
+    }
+
+    // Applies update_fn to the settings resolved at the given level and keeps
+    // whichever fields it changed in that level. A value that matches what is
+    // inherited anyway isn't stored, so it keeps following the levels below.
+    pub fn update<F, T>(&mut self, message: &Message, level: SettingsLevel, update_fn: F) -> T
+    where
+        F: FnOnce(&mut UserSettings) -> T,
+    {
+        let mut settings = self.resolve_up_to(message, level).settings;
+        let before = serde_json::to_value(&settings).unwrap_or(Value::Null);
+        let result = update_fn(&mut settings);
+        let after = serde_json::to_value(&settings).unwrap_or(Value::Null);
+        let key = match layer_key(message, level) {
+            Some(key) => key,
+            None => return result,
+        };
+        let changed: Vec<(String, Value)> = match (before, after) {
+            (Value::Object(before), Value::Object(after)) => after
+                .into_iter()
+                .filter(|(field_name, value)| before.get(field_name) != Some(value))
+                .collect(),
+            _ => Vec::new(),
+        };
+        if changed.is_empty() {
+            return result;
+        }
+        let layer = self.settings_hash_map.entry(key.clone()).or_default();
+        layer.extend(changed);
+        layer.insert("version".to_string(), Value::from(USER_SETTINGS_VERSION));
+        if let Err(why) = self.storage.save(&key, layer) {
+            error!("Unable to save settings for {}: {}", key, why);
+        }
+        result
+    }
+
+    // Forgets everything set at the given level, so the levels below apply again.
+    pub fn reset(&mut self, message: &Message, level: SettingsLevel) {
+        if let Some(key) = layer_key(message, level) {
+            if self.settings_hash_map.remove(&key).is_some() {
+                if let Err(why) = self.storage.remove(&key) {
+                    error!("Unable to remove settings for {}: {}", key, why);
+                }
+            }
+        }
+    }
+
+    // The stored name matching a user's name, ignoring case, as long as no
+    // other stored name matches it too.
+    pub fn legacy_settings_name(&self, user_name: &str) -> Result<String, LegacySettingsError> {
+        let mut legacy_names: Vec<String> = self
+            .legacy_settings_hash_map
+            .keys()
+            .filter(|legacy_name| legacy_name.to_lowercase() == user_name.to_lowercase())
+            .cloned()
+            .collect();
+        legacy_names.sort();
+        match legacy_names.len() {
+            0 => Err(LegacySettingsError::NotFound(user_name.to_string())),
+            1 => Ok(legacy_names.remove(0)),
+            _ => Err(LegacySettingsError::Ambiguous(user_name.to_string(), legacy_names)),
+        }
+    }
+
+    // Moves the settings stored under a name to a user's id and deletes the old
+    // entry. Anything the user has already set under their id wins.
+    pub fn migrate_legacy_settings(&mut self, legacy_name: &str, user_id: UserId) -> Result<(), LegacySettingsError> {
+        let legacy_settings = self
+            .legacy_settings_hash_map
+            .get(legacy_name)
+            .cloned()
+            .ok_or_else(|| LegacySettingsError::NotFound(legacy_name.to_string()))?;
+        let key = user_id.to_string();
+        let mut layer = legacy_settings;
+        if let Some(existing) = self.settings_hash_map.get(&key) {
+            layer.extend(existing.clone());
+        }
+        layer.insert("version".to_string(), Value::from(USER_SETTINGS_VERSION));
+        if let Err(why) = self.storage.save(&key, &layer) {
+            error!("Unable to save settings for {}: {}", key, why);
+            return Ok(());
+        }
+        self.settings_hash_map.insert(key, layer);
+        self.legacy_settings_hash_map.remove(legacy_name);
+        if let Err(why) = self.storage.remove(legacy_name) {
+            error!("Unable to remove old settings for {}: {}", legacy_name, why);
+        }
+        Ok(())
+    }
+
+    pub fn profiles(&self, user: &User) -> UserProfiles {
+        self.profiles_hash_map
+            .get(&user.id)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    pub fn update_profiles<F, T>(&mut self, user: &User, update_fn: F) -> T
+    where
+        F: FnOnce(&mut UserProfiles) -> T,
+    {
+        let profiles = self.profiles_hash_map.entry(user.id).or_default();
+        let result = update_fn(profiles);
+        let key = format!("profiles:{}", user.id);
+        let saved = match serde_json::to_value(&*profiles) {
+            Ok(Value::Object(values)) if !profiles.is_empty() => self.storage.save(&key, &values),
+            Ok(_) => {
+                self.profiles_hash_map.remove(&user.id);
+                self.storage.remove(&key)
+            }
+            Err(why) => Err(why.into()),
+        };
+        if let Err(why) = saved {
+            error!("Unable to save profiles for {}: {}", user.id, why);
+        }
+        result
+    }
+
+    pub fn flush(&self) -> Result<(), StorageError> {
+        self.storage.flush()
+    }
+
+    fn layers(&self, message: &Message, level: SettingsLevel) -> Vec<(SettingsLevel, &PartialSettings)> {
+        [SettingsLevel::Server, SettingsLevel::Channel, SettingsLevel::User]
+            .iter()
+            .filter(|&&layer_level| layer_level <= level)
+            .filter_map(|&layer_level| {
+                layer_key(message, layer_level)
+                    .and_then(|key| self.settings_hash_map.get(&key))
+                    .map(|layer| (layer_level, layer))
+            })
+            .collect()
+    }
+}
+
+fn layer_key(message: &Message, level: SettingsLevel) -> Option<String> {
+    match level {
+        SettingsLevel::Default => None,
+        SettingsLevel::Server => message.guild_id.map(|guild_id| format!("guild:{}", guild_id)),
+        SettingsLevel::Channel => Some(format!("channel:{}", message.channel_id)),
+        SettingsLevel::User => Some(message.author.id.to_string()),
+    }
+}
+
+// Version 1 stored every field of a user's settings. Only the ones that differ
+// from the defaults are kept, so server and channel defaults can show through.
+fn upgrade_partial_settings(settings: PartialSettings) -> PartialSettings {
+    let version = settings.get("version").and_then(|version| version.as_u64()).unwrap_or(0);
+    if version >= USER_SETTINGS_VERSION as u64 {
+        return settings;
+    }
+    let defaults = match serde_json::to_value(UserSettings::default()) {
+        Ok(Value::Object(defaults)) => defaults,
+        _ => PartialSettings::new(),
+    };
+    let mut upgraded: PartialSettings = settings
+        .into_iter()
+        .filter(|(field_name, value)| field_name != "version" && defaults.get(field_name) != Some(value))
+        .collect();
+    upgraded.insert("version".to_string(), Value::from(USER_SETTINGS_VERSION));
+    upgraded
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::storage::open_storage;
+    use logistic_robot_core::enums::{AssemblingMachineLevel, FurnaceLevel};
+    use serde_json::json;
+    use std::path::PathBuf;
+
+    pub(crate) fn storage_file(name: &str, contents: Value) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("logistic_robot_{}_{}.json", std::process::id(), name));
+        std::fs::write(&path, contents.to_string()).unwrap();
+        path
+    }
+
+    fn settings(value: Value) -> PartialSettings {
+        value.as_object().unwrap().clone()
+    }
+
+    fn stored_keys(user_settings_database: &UserSettingsDatabase) -> Vec<String> {
+        let mut keys: Vec<String> = user_settings_database.storage.load().unwrap().into_keys().collect();
+        keys.sort();
+        keys
+    }
+
+    pub(crate) fn message(guild_id: u64, channel_id: u64, user_id: u64, content: &str) -> Message {
+        serde_json::from_value(json!({
+            "id": 1,
+            "attachments": [],
+            "author": {"id": user_id.to_string(), "username": "Someone", "discriminator": "0001", "avatar": null},
+            "channel_id": channel_id.to_string(),
+            "content": content,
+            "edited_timestamp": null,
+            "embeds": [],
+            "guild_id": guild_id.to_string(),
+            "type": 0,
+            "mention_everyone": false,
+            "mention_roles": [],
+            "mentions": [],
+            "pinned": false,
+            "timestamp": "2021-01-01T00:00:00Z",
+            "tts": false,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn layers_server_channel_and_user_settings() {
+        let path = storage_file(
+            "layers",
+            json!({
+                "guild:1": {"version": 2, "assembling_machine_level": "Two", "furnace_level": "Steel"},
+                "channel:2": {"version": 2, "assembling_machine_level": "Three"},
+                "3": {"version": 2, "furnace_level": "Electric"},
+                "channel:9": {"version": 2, "whole_numbers": true},
+            }),
+        );
+        let user_settings_database = UserSettingsDatabase::load(open_storage("json", &path).unwrap()).unwrap();
+        let message = message(1, 2, 3, "!update-settings");
+
+        let resolved = user_settings_database.resolve(&message);
+        assert!(matches!(resolved.settings.assembling_machine_level, AssemblingMachineLevel::Three));
+        assert!(matches!(resolved.settings.furnace_level, FurnaceLevel::Electric));
+        assert!(!resolved.settings.whole_numbers);
+        assert_eq!(resolved.source("assembling_machine_level"), SettingsLevel::Channel);
+        assert_eq!(resolved.source("furnace_level"), SettingsLevel::User);
+        assert_eq!(resolved.source("belt_tier"), SettingsLevel::Default);
+
+        let server_defaults = user_settings_database.resolve_up_to(&message, SettingsLevel::Server);
+        assert!(matches!(server_defaults.settings.assembling_machine_level, AssemblingMachineLevel::Two));
+        assert!(matches!(server_defaults.settings.furnace_level, FurnaceLevel::Steel));
+        assert_eq!(server_defaults.source("furnace_level"), SettingsLevel::Server);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn stores_only_fields_that_differ_from_lower_levels() {
+        let path = storage_file(
+            "update",
+            json!({"guild:1": {"version": 2, "furnace_level": "Steel"}}),
+        );
+        let mut user_settings_database = UserSettingsDatabase::load(open_storage("json", &path).unwrap()).unwrap();
+        let message = message(1, 2, 3, "!update-settings");
+
+        // Setting what the server already gives stores nothing, so the user
+        // keeps following the server.
+        user_settings_database.update(&message, SettingsLevel::User, |settings| {
+            settings.furnace_level = FurnaceLevel::Steel;
+        });
+        assert!(!user_settings_database.settings_hash_map.contains_key("3"));
+
+        user_settings_database.update(&message, SettingsLevel::User, |settings| {
+            settings.furnace_level = FurnaceLevel::Steel;
+            settings.whole_numbers = true;
+        });
+        assert_eq!(
+            user_settings_database.settings_hash_map["3"],
+            settings(json!({"version": 2, "whole_numbers": true}))
+        );
+        user_settings_database.update(&message, SettingsLevel::Server, |settings| {
+            settings.furnace_level = FurnaceLevel::Electric;
+        });
+        let resolved = user_settings_database.resolve(&message);
+        assert!(matches!(resolved.settings.furnace_level, FurnaceLevel::Electric));
+        assert_eq!(resolved.source("furnace_level"), SettingsLevel::Server);
+        assert_eq!(resolved.source("whole_numbers"), SettingsLevel::User);
+
+        user_settings_database.reset(&message, SettingsLevel::User);
+        assert!(!user_settings_database.resolve(&message).settings.whole_numbers);
+        assert_eq!(stored_keys(&user_settings_database), vec!["guild:1"]);
+        let stored = user_settings_database.storage.load().unwrap();
+        assert_eq!(stored["guild:1"], settings(json!({"version": 2, "furnace_level": "Electric"})));
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn upgrades_version_one_settings_to_only_changed_fields() {
+        let mut version_one = match serde_json::to_value(UserSettings::default()).unwrap() {
+            Value::Object(values) => values,
+            _ => unreachable!(),
+        };
+        version_one.insert("version".to_string(), json!(1));
+        version_one.insert("furnace_level".to_string(), json!("Steel"));
+        assert_eq!(
+            upgrade_partial_settings(version_one),
+            settings(json!({"version": 2, "furnace_level": "Steel"}))
+        );
+
+        let current = settings(json!({"version": 2, "tree_depth": 3}));
+        assert_eq!(upgrade_partial_settings(current.clone()), current);
+    }
+
+    #[test]
+    fn skips_unreadable_profiles() {
+        let path = storage_file(
+            "bad_profiles",
+            json!({
+                "profiles:3": {"profiles": 3},
+                "3": {"version": 2, "whole_numbers": true},
+            }),
+        );
+        let user_settings_database = UserSettingsDatabase::load(open_storage("json", &path).unwrap()).unwrap();
+        let message = message(1, 2, 3, "!recipe inserter");
+        assert!(user_settings_database.profiles(&message.author).is_empty());
+        assert!(user_settings_database.resolve(&message).settings.whole_numbers);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn migrates_settings_claimed_by_a_unique_name() {
+        let path = storage_file(
+            "legacy",
+            json!({
+                "12": {"version": 2, "furnace_level": "Steel"},
+                "Someone": {"version": 2, "furnace_level": "Electric", "whole_numbers": true},
+            }),
+        );
+        let mut user_settings_database = UserSettingsDatabase::load(open_storage("json", &path).unwrap()).unwrap();
+        assert!(!user_settings_database.settings_hash_map.contains_key("Someone"));
+
+        let legacy_name = user_settings_database.legacy_settings_name("someone").unwrap();
+        assert_eq!(legacy_name, "Someone");
+        user_settings_database.migrate_legacy_settings(&legacy_name, UserId(12)).unwrap();
+        let layer = &user_settings_database.settings_hash_map["12"];
+        assert_eq!(layer["furnace_level"], json!("Steel"));
+        assert_eq!(layer["whole_numbers"], json!(true));
+        assert_eq!(stored_keys(&user_settings_database), vec!["12"]);
+        assert_eq!(
+            user_settings_database.legacy_settings_name("someone"),
+            Err(LegacySettingsError::NotFound("someone".to_string()))
+        );
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn refuses_to_guess_between_matching_names() {
+        let path = storage_file(
+            "ambiguous_legacy",
+            json!({
+                "Someone": {"version": 2, "furnace_level": "Electric"},
+                "someone": {"version": 2, "furnace_level": "Steel"},
+            }),
+        );
+        let mut user_settings_database = UserSettingsDatabase::load(open_storage("json", &path).unwrap()).unwrap();
+        assert_eq!(
+            user_settings_database.legacy_settings_name("SOMEONE"),
+            Err(LegacySettingsError::Ambiguous(
+                "SOMEONE".to_string(),
+                vec!["Someone".to_string(), "someone".to_string()]
+            ))
+        );
+        assert_eq!(stored_keys(&user_settings_database), vec!["Someone", "someone"]);
+
+        // A bot owner can still move one of them by its exact name.
+        user_settings_database.migrate_legacy_settings("someone", UserId(7)).unwrap();
+        assert_eq!(user_settings_database.settings_hash_map["7"]["furnace_level"], json!("Steel"));
+        assert_eq!(stored_keys(&user_settings_database), vec!["7", "Someone"]);
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,254 @@
+use core::fmt::Error;
+use core::fmt::Formatter;
+use fraction::Fraction;
+use logistic_robot_core::enums::{
+    parse_assembling_machine_level, parse_belt_tier, parse_furnace_level, AssemblingMachineLevel,
+    BeltTier, FurnaceLevel, TimeUnit,
+};
+use logistic_robot_core::rate::{parse_rate, parse_time_unit, RateParseError};
+use std::fmt::Display;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+
+pub const USAGE: &str = "Usage: logistic-robot recipe <items> [options]
+       logistic-robot --serve <address> [--materials <path>]
+
+Calculates the machines and raw materials needed to make <items>, a comma
+separated list where each item can be followed by its own rate.
+
+Options:
+  --rate <rate>          Items per second, or with a unit like 45/m (default 1/s)
+  --assembler <1|2|3>    Assembling machine tier
+  --furnace <tier>       stone, steel or electric
+  --belt <tier>          yellow, red or blue
+  --unit <unit>          Show rates per s, m or h
+  --whole                Scale up to whole numbers of machines
+  --import <items>       Comma separated items to treat as raw
+  --use <recipes>        Comma separated recipes to use instead of the defaults
+  --materials <path>     Use this materials.json instead of the built-in one
+  --format <format>      table, json or csv (default table)
+
+Serving:
+  --serve <address>      Answer JSON requests on an address like 127.0.0.1:8080:
+                           GET  /search?q=<name>&limit=<count>
+                           GET  /items/<name>
+                           POST /calculate with {\"items\", \"rate\", \"settings\",
+                                \"imports\", \"recipes\"}
+
+Example:
+  logistic-robot recipe \"logistic science pack\" --rate 5/s --assembler 3 --furnace steel --whole";
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum OutputFormat {
+    Table,
+    Json,
+    Csv,
+}
+
+pub enum Command {
+    Help,
+    Recipe(RecipeArguments),
+    Serve(ServeArguments),
+}
+
+pub struct RecipeArguments {
+    pub item_names: String,
+    pub rate: Fraction,
+    pub assembling_machine_level: Option<AssemblingMachineLevel>,
+    pub furnace_level: Option<FurnaceLevel>,
+    pub belt_tier: Option<BeltTier>,
+    pub display_unit: Option<TimeUnit>,
+    pub whole_numbers: bool,
+    pub import_names: Option<String>,
+    pub recipe_names: Option<String>,
+    pub materials_path: Option<PathBuf>,
+    pub output_format: OutputFormat,
+}
+
+pub struct ServeArguments {
+    pub address: SocketAddr,
+    pub materials_path: Option<PathBuf>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ArgumentError {
+    MissingCommand,
+    UnknownCommand(String),
+    UnknownArgument(String),
+    MissingArgumentValue(String),
+    MissingItems,
+    InvalidRate(RateParseError),
+    InvalidAssemblingMachineLevel(String),
+    InvalidFurnaceLevel(String),
+    InvalidBeltTier(String),
+    InvalidTimeUnit(String),
+    InvalidOutputFormat(String),
+    InvalidAddress(String),
+}
+impl Display for ArgumentError {
+    fn fmt(&self, formatter: &mut Formatter) -> Result<(), Error> {
+        match self {
+            ArgumentError::MissingCommand => write!(formatter, "No command given"),
+            ArgumentError::UnknownCommand(command) => {
+                write!(formatter, "Unknown command {}, expected recipe or help", command)
+            }
+            ArgumentError::UnknownArgument(argument) => write!(formatter, "Unknown argument {}", argument),
+            ArgumentError::MissingArgumentValue(argument) => {
+                write!(formatter, "No value found for {}", argument)
+            }
+            ArgumentError::MissingItems => write!(formatter, "No items given"),
+            ArgumentError::InvalidRate(why) => write!(formatter, "{}", why),
+            ArgumentError::InvalidAssemblingMachineLevel(level) => write!(
+                formatter,
+                "Invalid assembling machine level {}, expected 1, 2 or 3",
+                level
+            ),
+            ArgumentError::InvalidFurnaceLevel(level) => write!(
+                formatter,
+                "Invalid furnace level {}, expected stone, steel or electric",
+                level
+            ),
+            ArgumentError::InvalidBeltTier(belt_tier) => write!(
+                formatter,
+                "Invalid belt {}, expected yellow, red or blue",
+                belt_tier
+            ),
+            ArgumentError::InvalidTimeUnit(unit) => {
+                write!(formatter, "Invalid time unit {}, expected s, m or h", unit)
+            }
+            ArgumentError::InvalidOutputFormat(output_format) => write!(
+                formatter,
+                "Invalid format {}, expected table, json or csv",
+                output_format
+            ),
+            ArgumentError::InvalidAddress(address) => write!(
+                formatter,
+                "Invalid address {}, expected an ip and port like 127.0.0.1:8080",
+                address
+            ),
+        }
+    }
+}
+
+pub fn parse_arguments(arguments: Vec<String>) -> Result<Command, ArgumentError> {
+    let mut argument_iter = arguments.into_iter();
+    match argument_iter.next().as_deref() {
+        Some("recipe") => parse_recipe_arguments(argument_iter).map(Command::Recipe),
+        Some("help") | Some("--help") | Some("-h") => Ok(Command::Help),
+        Some(argument) if argument == "--serve" || argument.starts_with("--serve=") => {
+            parse_serve_arguments(std::iter::once(argument.to_string()).chain(argument_iter))
+                .map(Command::Serve)
+        }
+        Some(command) => Err(ArgumentError::UnknownCommand(command.to_string())),
+        None => Err(ArgumentError::MissingCommand),
+    }
+}
+
+// Words that aren't options make up the item list, so quoting item names is
+// optional. Options accept both "--name value" and "--name=value".
+fn parse_recipe_arguments<I>(mut argument_iter: I) -> Result<RecipeArguments, ArgumentError>
+where
+    I: Iterator<Item = String>,
+{
+    let mut item_words: Vec<String> = Vec::new();
+    let mut recipe_arguments = RecipeArguments {
+        item_names: String::new(),
+        rate: Fraction::from(1),
+        assembling_machine_level: None,
+        furnace_level: None,
+        belt_tier: None,
+        display_unit: None,
+        whole_numbers: false,
+        import_names: None,
+        recipe_names: None,
+        materials_path: None,
+        output_format: OutputFormat::Table,
+    };
+    while let Some(argument) = argument_iter.next() {
+        if !argument.starts_with("--") {
+            item_words.push(argument);
+            continue;
+        }
+        let (name, inline_value) = match argument.split_once('=') {
+            Some((name, value)) => (name.to_string(), Some(value.to_string())),
+            None => (argument.clone(), None),
+        };
+        if name == "--whole" {
+            recipe_arguments.whole_numbers = true;
+            continue;
+        }
+        let value = match name.as_str() {
+            "--rate" | "--assembler" | "--furnace" | "--belt" | "--unit" | "--import" | "--use"
+            | "--materials" | "--format" => inline_value
+                .or_else(|| argument_iter.next())
+                .ok_or_else(|| ArgumentError::MissingArgumentValue(name.clone()))?,
+            _ => return Err(ArgumentError::UnknownArgument(argument)),
+        };
+        match name.as_str() {
+            "--rate" => recipe_arguments.rate = parse_rate(&value).map_err(ArgumentError::InvalidRate)?,
+            "--assembler" => {
+                recipe_arguments.assembling_machine_level = Some(
+                    parse_assembling_machine_level(&value)
+                        .ok_or(ArgumentError::InvalidAssemblingMachineLevel(value))?,
+                )
+            }
+            "--furnace" => {
+                recipe_arguments.furnace_level =
+                    Some(parse_furnace_level(&value).ok_or(ArgumentError::InvalidFurnaceLevel(value))?)
+            }
+            "--belt" => {
+                recipe_arguments.belt_tier =
+                    Some(parse_belt_tier(&value).ok_or(ArgumentError::InvalidBeltTier(value))?)
+            }
+            "--unit" => {
+                recipe_arguments.display_unit =
+                    Some(parse_time_unit(&value).ok_or(ArgumentError::InvalidTimeUnit(value))?)
+            }
+            "--import" => recipe_arguments.import_names = Some(value),
+            "--use" => recipe_arguments.recipe_names = Some(value),
+            "--materials" => recipe_arguments.materials_path = Some(PathBuf::from(value)),
+            _ => {
+                recipe_arguments.output_format = match &*value.to_lowercase() {
+                    "table" => OutputFormat::Table,
+                    "json" => OutputFormat::Json,
+                    "csv" => OutputFormat::Csv,
+                    _ => return Err(ArgumentError::InvalidOutputFormat(value)),
+                }
+            }
+        }
+    }
+    recipe_arguments.item_names = item_words.join(" ");
+    if recipe_arguments.item_names.trim().is_empty() {
+        return Err(ArgumentError::MissingItems);
+    }
+    Ok(recipe_arguments)
+}
+
+fn parse_serve_arguments<I>(mut argument_iter: I) -> Result<ServeArguments, ArgumentError>
+where
+    I: Iterator<Item = String>,
+{
+    let mut address = None;
+    let mut materials_path = None;
+    while let Some(argument) = argument_iter.next() {
+        let (name, inline_value) = match argument.split_once('=') {
+            Some((name, value)) => (name.to_string(), Some(value.to_string())),
+            None => (argument.clone(), None),
+        };
+        if name != "--serve" && name != "--materials" {
+            return Err(ArgumentError::UnknownArgument(argument));
+        }
+        let value = inline_value
+            .or_else(|| argument_iter.next())
+            .ok_or_else(|| ArgumentError::MissingArgumentValue(name.clone()))?;
+        if name == "--serve" {
+            address = Some(value.parse::<SocketAddr>().map_err(|_| ArgumentError::InvalidAddress(value))?);
+        } else {
+            materials_path = Some(PathBuf::from(value));
+        }
+    }
+    Ok(ServeArguments {
+        address: address.ok_or_else(|| ArgumentError::MissingArgumentValue("--serve".to_string()))?,
+        materials_path,
+    })
+}
@@ -0,0 +1,101 @@
+use crate::arguments::{parse_arguments, Command, OutputFormat, RecipeArguments, ServeArguments, USAGE};
+use logistic_robot_core::report::CalculationReport;
+use logistic_robot_core::{Calculator, CalculatorError, MaterialDatabase, UserSettings};
+use std::path::PathBuf;
+
+mod arguments;
+mod output;
+mod server;
+
+fn main() {
+    let command = match parse_arguments(std::env::args().skip(1).collect()) {
+        Ok(command) => command,
+        Err(why) => {
+            eprintln!("{}\n\n{}", plain(&why.to_string()), USAGE);
+            std::process::exit(2);
+        }
+    };
+    let result = match command {
+        Command::Help => {
+            println!("{}", USAGE);
+            Ok(())
+        }
+        Command::Recipe(recipe_arguments) => recipe(recipe_arguments),
+        Command::Serve(serve_arguments) => serve(serve_arguments),
+    };
+    if let Err(why) = result {
+        eprintln!("{}", plain(&why));
+        std::process::exit(1);
+    }
+}
+
+fn recipe(recipe_arguments: RecipeArguments) -> Result<(), String> {
+    let material_database = load_material_database(&recipe_arguments.materials_path)?;
+    let mut user_settings = UserSettings::default();
+    if let Some(belt_tier) = recipe_arguments.belt_tier {
+        user_settings.belt_tier = belt_tier;
+    }
+    if let Some(display_unit) = recipe_arguments.display_unit {
+        user_settings.display_unit = display_unit;
+    }
+    let mut calculator = Calculator::new(&material_database)
+        .settings(user_settings)
+        .items(&recipe_arguments.item_names)
+        .rate(recipe_arguments.rate)
+        .whole_numbers(recipe_arguments.whole_numbers);
+    if let Some(assembling_machine_level) = recipe_arguments.assembling_machine_level {
+        calculator = calculator.assembling_machine_level(assembling_machine_level);
+    }
+    if let Some(furnace_level) = recipe_arguments.furnace_level {
+        calculator = calculator.furnace_level(furnace_level);
+    }
+    if let Some(import_names) = &recipe_arguments.import_names {
+        calculator = calculator.imports(import_names);
+    }
+    if let Some(recipe_names) = &recipe_arguments.recipe_names {
+        calculator = calculator.recipes(recipe_names);
+    }
+    let calculation = calculator.calculate().map_err(|why| match why {
+        CalculatorError::Unsolvable(why) => {
+            format!("Unable to calculate {}: {}", recipe_arguments.item_names, why)
+        }
+        why => why.to_string(),
+    })?;
+
+    let report = CalculationReport::new(&calculation);
+    let stdout = std::io::stdout();
+    match recipe_arguments.output_format {
+        OutputFormat::Table => output::write_table(stdout.lock(), &calculation, &report),
+        OutputFormat::Json => output::write_json(stdout.lock(), &report).map(|()| println!()),
+        OutputFormat::Csv => output::write_csv(stdout.lock(), &report),
+    }
+}
+
+// Only serving needs an async runtime, so it's started here rather than for
+// every command.
+fn serve(serve_arguments: ServeArguments) -> Result<(), String> {
+    let material_database = load_material_database(&serve_arguments.materials_path)?;
+    tokio::runtime::Runtime::new()
+        .map_err(|why| format!("Unable to start the server runtime: {}", why))?
+        .block_on(server::serve(serve_arguments.address, material_database))
+}
+
+fn load_material_database(materials_path: &Option<PathBuf>) -> Result<MaterialDatabase, String> {
+    let (file_name, possible_material_database) = match materials_path {
+        Some(materials_path) => {
+            let json_file_contents = std::fs::read_to_string(materials_path)
+                .map_err(|why| format!("Unable to read {}: {}", materials_path.display(), why))?;
+            (materials_path.display().to_string(), MaterialDatabase::new(json_file_contents))
+        }
+        None => ("built-in materials.json".to_string(), MaterialDatabase::built_in()),
+    };
+    possible_material_database.map_err(|why| {
+        let problems: Vec<String> = why.problems.iter().map(|problem| format!("  {}", problem)).collect();
+        format!("Unable to load {}:\n{}", file_name, problems.join("\n"))
+    })
+}
+
+// The shared messages are written for Discord, where *text* is italic.
+fn plain(message: &str) -> String {
+    message.replace('*', "")
+}
@@ -0,0 +1,281 @@
+use logistic_robot_core::format;
+use logistic_robot_core::report::{CalculationReport, ItemRate};
+use logistic_robot_core::Calculation;
+use serde::Serialize;
+use std::io::Write;
+
+pub fn write_json<W: Write>(writer: W, report: &CalculationReport) -> Result<(), String> {
+    serde_json::to_writer_pretty(writer, report).map_err(|why| why.to_string())
+}
+
+// One row per item, machine or total, sharing one set of columns. Machines get
+// a row for each item they make, and belts fill in their own columns. Numbers
+// come twice, as a decimal and as an exact fraction.
+#[derive(Default, Serialize)]
+struct CsvRow {
+    section: &'static str,
+    name: String,
+    item: String,
+    rate: Option<f64>,
+    rate_exact: Option<String>,
+    unit: String,
+    machine: Option<String>,
+    count: Option<f64>,
+    count_exact: Option<String>,
+    belt: Option<String>,
+    belts: Option<f64>,
+    belts_exact: Option<String>,
+    lanes: Option<f64>,
+    lanes_exact: Option<String>,
+}
+
+pub fn write_csv<W: Write>(writer: W, report: &CalculationReport) -> Result<(), String> {
+    let mut rows: Vec<CsvRow> = Vec::new();
+    let unit = &report.unit;
+    let item_row = |section: &'static str, item_rate: &ItemRate| CsvRow {
+        section,
+        name: item_rate.item.clone(),
+        item: item_rate.item.clone(),
+        rate: Some(item_rate.rate),
+        rate_exact: Some(item_rate.rate_exact.clone()),
+        unit: unit.clone(),
+        ..CsvRow::default()
+    };
+    let total_row = |section: &'static str, name: &str, rate: f64, rate_exact: &str, unit: &str| CsvRow {
+        section,
+        name: name.to_string(),
+        rate: Some(rate),
+        rate_exact: Some(rate_exact.to_string()),
+        unit: unit.to_string(),
+        ..CsvRow::default()
+    };
+    rows.extend(report.targets.iter().map(|target| item_row("target", target)));
+    for machine_count in &report.machines {
+        for output in &machine_count.outputs {
+            rows.push(CsvRow {
+                name: machine_count.recipe.clone(),
+                machine: Some(machine_count.machine.clone()),
+                count: Some(machine_count.count),
+                count_exact: Some(machine_count.count_exact.clone()),
+                ..item_row("machines", output)
+            });
+        }
+    }
+    for raw_rate in &report.raw {
+        rows.push(CsvRow {
+            section: "raw",
+            name: raw_rate.item.clone(),
+            item: raw_rate.item.clone(),
+            rate: Some(raw_rate.rate),
+            rate_exact: Some(raw_rate.rate_exact.clone()),
+            unit: unit.clone(),
+            machine: raw_rate.extractor.clone(),
+            count: raw_rate.extractors,
+            count_exact: raw_rate.extractors_exact.clone(),
+            ..CsvRow::default()
+        });
+    }
+    rows.extend(report.surplus.iter().map(|surplus| item_row("surplus", surplus)));
+    for belt_count in &report.belts {
+        rows.push(CsvRow {
+            section: "belts",
+            name: belt_count.item.clone(),
+            item: belt_count.item.clone(),
+            rate: Some(belt_count.rate),
+            rate_exact: Some(belt_count.rate_exact.clone()),
+            unit: unit.clone(),
+            belt: Some(format!("{} Belt", belt_count.belt)),
+            belts: Some(belt_count.belts),
+            belts_exact: Some(belt_count.belts_exact.clone()),
+            lanes: Some(belt_count.lanes),
+            lanes_exact: Some(belt_count.lanes_exact.clone()),
+            ..CsvRow::default()
+        });
+    }
+    let power = &report.power;
+    rows.push(total_row("power", "Total", power.total_mw, &power.total_mw_exact, "MW"));
+    rows.push(total_row("power", "Idle drain", power.drain_mw, &power.drain_mw_exact, "MW"));
+    rows.push(total_row(
+        "pollution",
+        "Total",
+        report.pollution_per_minute,
+        &report.pollution_per_minute_exact,
+        "minute",
+    ));
+
+    let mut csv_writer = csv::Writer::from_writer(writer);
+    for row in rows {
+        csv_writer.serialize(row).map_err(|why| why.to_string())?;
+    }
+    csv_writer.flush().map_err(|why| why.to_string())
+}
+
+pub fn write_table<W: Write>(
+    mut writer: W,
+    calculation: &Calculation,
+    report: &CalculationReport,
+) -> Result<(), String> {
+    let suffix = calculation.settings.display_unit.suffix();
+    let mut lines: Vec<String> = vec![format::title(calculation), String::new()];
+    lines.extend(table(
+        &["Recipe", "Machines", "Machine", "Output"],
+        report
+            .machines
+            .iter()
+            .map(|machine_count| {
+                vec![
+                    machine_count.recipe.clone(),
+                    print_number(machine_count.count),
+                    machine_count.machine.clone(),
+                    print_outputs(&machine_count.recipe, &machine_count.outputs, suffix),
+                ]
+            })
+            .collect(),
+    ));
+    lines.push(String::new());
+    lines.extend(table(
+        &["Raw", "Rate", "Extractors"],
+        report
+            .raw
+            .iter()
+            .map(|raw_rate| {
+                vec![
+                    raw_rate.item.clone(),
+                    format!("{}{}", print_number(raw_rate.rate), suffix),
+                    match (&raw_rate.extractor, raw_rate.extractors) {
+                        (Some(extractor), Some(extractors)) => format!(
+                            "{} {}{}",
+                            print_number(extractors),
+                            extractor,
+                            if extractors == 1.0 { "" } else { "s" }
+                        ),
+                        _ => String::new(),
+                    },
+                ]
+            })
+            .collect(),
+    ));
+    if !report.surplus.is_empty() {
+        lines.push(String::new());
+        lines.extend(table(
+            &["Surplus", "Rate"],
+            report
+                .surplus
+                .iter()
+                .map(|surplus| vec![surplus.item.clone(), format!("{}{}", print_number(surplus.rate), suffix)])
+                .collect(),
+        ));
+    }
+    if !report.belts.is_empty() {
+        lines.push(String::new());
+        lines.extend(table(
+            &["Belted item", "Belts", "Lanes"],
+            report
+                .belts
+                .iter()
+                .map(|belt_count| {
+                    vec![
+                        belt_count.item.clone(),
+                        format!(
+                            "{} {} Belt{}",
+                            print_number(belt_count.belts),
+                            belt_count.belt,
+                            if belt_count.belts == 1.0 { "" } else { "s" }
+                        ),
+                        print_number(belt_count.lanes),
+                    ]
+                })
+                .collect(),
+        ));
+    }
+    lines.push(String::new());
+    lines.push(format!(
+        "Power: {} MW ({} MW idle drain)",
+        print_number(report.power.total_mw),
+        print_number(report.power.drain_mw)
+    ));
+    if report.power.coal > 0.0 {
+        lines.push(format!("Coal for burner machines: {}{}", print_number(report.power.coal), suffix));
+    }
+    lines.push(format!("Pollution: {} per minute", print_number(report.pollution_per_minute)));
+    for module_warning in &report.module_warnings {
+        lines.push(format!("Modules left out: {}", module_warning));
+    }
+    lines.push(format::footer(calculation));
+    for line in lines {
+        writeln!(writer, "{}", line).map_err(|why| why.to_string())?;
+    }
+    Ok(())
+}
+
+// Left aligned columns padded to their widest cell, with a rule under the
+// header.
+fn table(header: &[&str], rows: Vec<Vec<String>>) -> Vec<String> {
+    let mut widths: Vec<usize> = header.iter().map(|cell| cell.chars().count()).collect();
+    for row in &rows {
+        for (index, cell) in row.iter().enumerate() {
+            widths[index] = widths[index].max(cell.chars().count());
+        }
+    }
+    let format_row = |cells: Vec<String>| {
+        cells
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+            .collect::<Vec<String>>()
+            .join("  ")
+            .trim_end()
+            .to_string()
+    };
+    let mut lines = vec![
+        format_row(header.iter().map(|cell| cell.to_string()).collect()),
+        format_row(widths.iter().map(|width| "-".repeat(*width)).collect()),
+    ];
+    lines.extend(rows.into_iter().map(format_row));
+    lines
+}
+
+// A recipe making only the item it's named after just shows the rate.
+fn print_outputs(recipe_name: &str, outputs: &[ItemRate], suffix: &str) -> String {
+    match outputs {
+        [output] if output.item == recipe_name => format!("{}{}", print_number(output.rate), suffix),
+        _ => outputs
+            .iter()
+            .map(|output| format!("{} {}{}", output.item, print_number(output.rate), suffix))
+            .collect::<Vec<String>>()
+            .join(", "),
+    }
+}
+
+// At most two decimals, without trailing zeros.
+fn print_number(number: f64) -> String {
+    let printed = format!("{:.2}", number);
+    printed.trim_end_matches('0').trim_end_matches('.').to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fraction::Fraction;
+    use logistic_robot_core::{Calculator, MaterialDatabase};
+
+    #[test]
+    fn writes_belts_in_their_own_columns() {
+        let material_database = MaterialDatabase::built_in().unwrap();
+        let calculation = Calculator::new(&material_database)
+            .items("iron gear wheel")
+            .rate(Fraction::new(16u64, 5u64))
+            .calculate()
+            .unwrap();
+        let mut csv = Vec::new();
+        write_csv(&mut csv, &CalculationReport::new(&calculation)).unwrap();
+        let csv = String::from_utf8(csv).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(
+            lines[0],
+            "section,name,item,rate,rate_exact,unit,machine,count,count_exact,belt,belts,belts_exact,lanes,lanes_exact"
+        );
+        assert!(lines.contains(&"target,Iron Gear Wheel,Iron Gear Wheel,3.2,16/5,second,,,,,,,,"));
+        assert!(lines.contains(&"belts,Iron Gear Wheel,Iron Gear Wheel,3.2,16/5,second,,,,Yellow Belt,0.21333333333333335,16/75,0.4266666666666667,32/75"));
+    }
+}
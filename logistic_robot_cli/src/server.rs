@@ -0,0 +1,162 @@
+use crate::plain;
+use axum::extract::rejection::JsonRejection;
+use axum::extract::{DefaultBodyLimit, Path, Query, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use fraction::Fraction;
+use logistic_robot_core::rate::parse_rate;
+use logistic_robot_core::report::{CalculationReport, ItemInfo};
+use logistic_robot_core::{Calculator, CalculatorError, MaterialDatabase, UserSettings};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+const DEFAULT_SEARCH_RESULTS: usize = 10;
+const MAX_SEARCH_RESULTS: usize = 50;
+// A calculation request is a few item names and settings; anything much
+// bigger isn't one. Rates are capped by parse_rate.
+const MAX_BODY_BYTES: usize = 16 * 1024;
+const MAX_TARGETS: usize = 20;
+
+#[derive(Deserialize)]
+struct SearchQuery {
+    q: String,
+    limit: Option<usize>,
+}
+
+#[derive(Serialize)]
+struct SearchResult {
+    name: String,
+    aliases: Vec<String>,
+}
+
+// Settings take the same fields as stored user settings, and any left out
+// keep their defaults.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct CalculateRequest {
+    items: String,
+    rate: Option<String>,
+    #[serde(default)]
+    settings: UserSettings,
+    imports: Option<String>,
+    recipes: Option<String>,
+}
+
+// Every error is sent as {"error": "..."}.
+struct ApiError(StatusCode, String);
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let mut body = HashMap::new();
+        body.insert("error", plain(&self.1));
+        (self.0, Json(body)).into_response()
+    }
+}
+
+// GET /search?q=gear&limit=10, GET /items/{name} and POST /calculate, all
+// answered from the one database loaded at startup. The server runs in the
+// CLI rather than the bot, so it needs no Discord token, and loads the same
+// built-in or --materials data the bot does.
+pub async fn serve(address: SocketAddr, material_database: MaterialDatabase) -> Result<(), String> {
+    let router = Router::new()
+        .route("/search", get(search))
+        .route("/items/:name", get(item_info))
+        .route("/calculate", post(calculate))
+        .layer(DefaultBodyLimit::max(MAX_BODY_BYTES))
+        .with_state(Arc::new(material_database));
+    let listener = tokio::net::TcpListener::bind(address)
+        .await
+        .map_err(|why| format!("Unable to listen on {}: {}", address, why))?;
+    eprintln!("Listening on http://{}", address);
+    axum::serve(listener, router)
+        .with_graceful_shutdown(async {
+            tokio::signal::ctrl_c().await.ok();
+        })
+        .await
+        .map_err(|why| format!("Server error: {}", why))
+}
+
+async fn search(
+    State(material_database): State<Arc<MaterialDatabase>>,
+    Query(search_query): Query<SearchQuery>,
+) -> Json<Vec<SearchResult>> {
+    let limit = search_query.limit.unwrap_or(DEFAULT_SEARCH_RESULTS).min(MAX_SEARCH_RESULTS);
+    Json(
+        material_database
+            .search_materials(&search_query.q, &HashMap::new(), limit)
+            .into_iter()
+            .map(|material| SearchResult {
+                name: material.name.clone(),
+                aliases: material.aliases.clone(),
+            })
+            .collect(),
+    )
+}
+
+async fn item_info(
+    State(material_database): State<Arc<MaterialDatabase>>,
+    Path(name): Path<String>,
+) -> Result<Json<ItemInfo>, ApiError> {
+    let material = material_database
+        .lookup_material(&name, &HashMap::new())
+        .map_err(|why| ApiError(StatusCode::NOT_FOUND, why.to_string()))?;
+    Ok(Json(ItemInfo::new(&material_database, material)))
+}
+
+async fn calculate(
+    State(material_database): State<Arc<MaterialDatabase>>,
+    possible_request: Result<Json<CalculateRequest>, JsonRejection>,
+) -> Result<Json<CalculationReport>, ApiError> {
+    let Json(CalculateRequest {
+        items: item_names,
+        rate,
+        settings: user_settings,
+        imports: import_names,
+        recipes: recipe_names,
+    }) = possible_request.map_err(|why| ApiError(why.status(), why.body_text()))?;
+    if item_names.split(',').count() > MAX_TARGETS {
+        return Err(ApiError(
+            StatusCode::BAD_REQUEST,
+            format!("At most {} items can be calculated at once", MAX_TARGETS),
+        ));
+    }
+    user_settings
+        .validate(&material_database)
+        .map_err(|why| ApiError(StatusCode::BAD_REQUEST, why.to_string()))?;
+    let rate = match &rate {
+        Some(rate) => parse_rate(rate).map_err(|why| ApiError(StatusCode::BAD_REQUEST, why.to_string()))?,
+        None => Fraction::from(1),
+    };
+    // Large production chains take a while to solve, so keep them off the
+    // threads serving other requests.
+    tokio::task::spawn_blocking(move || {
+        let mut calculator = Calculator::new(&material_database)
+            .settings(user_settings)
+            .items(&item_names)
+            .rate(rate);
+        if let Some(import_names) = &import_names {
+            calculator = calculator.imports(import_names);
+        }
+        if let Some(recipe_names) = &recipe_names {
+            calculator = calculator.recipes(recipe_names);
+        }
+        calculator
+            .calculate()
+            .map(|calculation| Json(CalculationReport::new(&calculation)))
+            .map_err(|why| match why {
+                CalculatorError::InvalidRate(why) => ApiError(StatusCode::BAD_REQUEST, why.to_string()),
+                CalculatorError::UnknownItem(why) | CalculatorError::UnknownRecipe(why) => {
+                    ApiError(StatusCode::NOT_FOUND, why.to_string())
+                }
+                CalculatorError::Unsolvable(why) => ApiError(
+                    StatusCode::UNPROCESSABLE_ENTITY,
+                    format!("Unable to calculate {}: {}", item_names, why),
+                ),
+            })
+    })
+    .await
+    .map_err(|why| ApiError(StatusCode::INTERNAL_SERVER_ERROR, why.to_string()))?
+}
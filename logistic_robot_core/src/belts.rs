@@ -0,0 +1,85 @@
+use crate::enums::BeltTier;
+use fraction::Fraction;
+
+impl BeltTier {
+    // Items per second carried by a full belt, both lanes together.
+    pub fn items_per_second(&self) -> Fraction {
+        Fraction::from(match self {
+            BeltTier::Yellow => 15,
+            BeltTier::Red => 30,
+            BeltTier::Blue => 45,
+        })
+    }
+
+    pub fn belts_required(&self, rate: Fraction) -> Fraction {
+        rate / self.items_per_second()
+    }
+
+    pub fn lanes_required(&self, rate: Fraction) -> Fraction {
+        self.belts_required(rate) * Fraction::from(2)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::format;
+    use crate::materials::material_database::MaterialDatabase;
+    use crate::user_settings::UserSettings;
+    use crate::Calculator;
+
+    #[test]
+    fn fills_a_belt_at_its_capacity() {
+        for (belt_tier, capacity) in [(BeltTier::Yellow, 15u64), (BeltTier::Red, 30), (BeltTier::Blue, 45)] {
+            assert_eq!(belt_tier.belts_required(Fraction::from(capacity)), Fraction::from(1));
+            assert_eq!(belt_tier.lanes_required(Fraction::from(capacity)), Fraction::from(2));
+            assert_eq!(belt_tier.lanes_required(Fraction::new(capacity, 2u64)), Fraction::from(1));
+            assert_eq!(belt_tier.belts_required(Fraction::from(capacity * 3)), Fraction::from(3));
+            assert!(belt_tier.belts_required(Fraction::from(capacity) + Fraction::new(1u64, 1000u64)) > Fraction::from(1));
+        }
+        assert_eq!(BeltTier::Red.belts_required(Fraction::from(15)), Fraction::new(1u64, 2u64));
+        assert_eq!(BeltTier::Blue.belts_required(Fraction::from(15)), Fraction::new(1u64, 3u64));
+    }
+
+    fn iron_plate_belts(belt_tier: BeltTier, rate: Fraction) -> String {
+        let material_database = MaterialDatabase::built_in().unwrap();
+        let calculation = Calculator::new(&material_database)
+            .settings(UserSettings { belt_tier, ..UserSettings::default() })
+            .items("iron plate")
+            .rate(rate)
+            .calculate()
+            .unwrap();
+        format::belt_lines(&calculation)
+            .into_iter()
+            .find(|line| line.starts_with("Iron Plate:"))
+            .unwrap()
+    }
+
+    #[test]
+    fn prints_belts_at_exact_multiples() {
+        assert_eq!(
+            iron_plate_belts(BeltTier::Yellow, Fraction::from(15)),
+            "Iron Plate: **1.00** Yellow Belt (**2.00** lanes)"
+        );
+        assert_eq!(
+            iron_plate_belts(BeltTier::Yellow, Fraction::from(30)),
+            "Iron Plate: **2.00** Yellow Belts (**4.00** lanes)"
+        );
+        assert_eq!(
+            iron_plate_belts(BeltTier::Red, Fraction::from(15)),
+            "Iron Plate: **0.50** Red Belts (**1.00** lanes)"
+        );
+        assert_eq!(
+            iron_plate_belts(BeltTier::Blue, Fraction::from(45)),
+            "Iron Plate: **1.00** Blue Belt (**2.00** lanes)"
+        );
+        assert_eq!(
+            iron_plate_belts(BeltTier::Blue, Fraction::new(44999u64, 1000u64)),
+            "Iron Plate: **1.00** Blue Belt (**2.00** lanes)"
+        );
+        assert_eq!(
+            iron_plate_belts(BeltTier::Blue, Fraction::from(46)),
+            "Iron Plate: **1.02** Blue Belts (**2.04** lanes)"
+        );
+    }
+}
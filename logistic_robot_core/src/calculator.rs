@@ -0,0 +1,347 @@
+use crate::enums::{AssemblingMachineLevel, Extractor, FurnaceLevel};
+use crate::materials::material::Material;
+use crate::materials::material_database::MaterialDatabase;
+use crate::materials::name_lookup::NameLookupError;
+use crate::pollution::{estimate_pollution, PollutionEstimate};
+use crate::power::{estimate_power, PowerEstimate};
+use crate::rate::{parse_rate, RateParseError};
+use crate::recipe::Recipe;
+use crate::solver::SolverError;
+use crate::total_raw_result::ProductionNode;
+use crate::user_settings::UserSettings;
+use core::fmt::Error;
+use core::fmt::Formatter;
+use fraction::Fraction;
+use std::collections::HashMap;
+use std::fmt::Display;
+
+// A raw material's rate per second and, for ores and fluids that are mined or
+// pumped, the extractors needed for it.
+pub type RawRequirement<'a> = (&'a Material, Fraction, Option<(Extractor, Fraction)>);
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum CalculatorError {
+    InvalidRate(RateParseError),
+    UnknownItem(NameLookupError),
+    UnknownRecipe(NameLookupError),
+    Unsolvable(SolverError),
+}
+impl Display for CalculatorError {
+    fn fmt(&self, formatter: &mut Formatter) -> Result<(), Error> {
+        match self {
+            CalculatorError::InvalidRate(why) => write!(formatter, "{}", why),
+            CalculatorError::UnknownItem(why) => write!(formatter, "{}", why),
+            CalculatorError::UnknownRecipe(why) => write!(formatter, "{}", why),
+            CalculatorError::Unsolvable(why) => write!(formatter, "{}", why),
+        }
+    }
+}
+
+/// Collects what to calculate and with which settings; names are only looked
+/// up once calculate is called, so that is where every error surfaces.
+///
+/// ```
+/// use fraction::Fraction;
+/// use logistic_robot_core::enums::AssemblingMachineLevel;
+/// use logistic_robot_core::{Calculator, MaterialDatabase};
+///
+/// let material_database = MaterialDatabase::built_in().unwrap();
+/// let calculation = Calculator::new(&material_database)
+///     .items("logistic science pack")
+///     .rate(Fraction::from(5))
+///     .assembling_machine_level(AssemblingMachineLevel::Three)
+///     .calculate()
+///     .unwrap();
+/// assert_eq!(calculation.targets[0].0.name, "Logistic Science Pack");
+/// ```
+pub struct Calculator<'a> {
+    material_database: &'a MaterialDatabase,
+    settings: UserSettings,
+    aliases: HashMap<String, String>,
+    item_names: Vec<String>,
+    rate: Fraction,
+    import_names: Vec<String>,
+    recipe_names: Vec<String>,
+}
+impl<'a> Calculator<'a> {
+    pub fn new(material_database: &'a MaterialDatabase) -> Self {
+        Calculator {
+            material_database,
+            settings: UserSettings::default(),
+            aliases: HashMap::new(),
+            item_names: Vec::new(),
+            rate: Fraction::from(1),
+            import_names: Vec::new(),
+            recipe_names: Vec::new(),
+        }
+    }
+
+    pub fn settings(mut self, settings: UserSettings) -> Self {
+        self.settings = settings;
+        self
+    }
+
+    // Extra names for items, lowercase alias to item name, checked before the
+    // aliases shipped with the data.
+    pub fn aliases(mut self, aliases: HashMap<String, String>) -> Self {
+        self.aliases = aliases;
+        self
+    }
+
+    // A comma separated list of items, each optionally followed by its own
+    // rate, e.g. "automation science pack 1, military science pack 0.5".
+    pub fn items(mut self, item_names: &str) -> Self {
+        self.item_names.push(item_names.to_string());
+        self
+    }
+
+    // Items per second for every item given without a rate of its own.
+    pub fn rate(mut self, rate: Fraction) -> Self {
+        self.rate = rate;
+        self
+    }
+
+    // A comma separated list of items to treat as raw.
+    pub fn imports(mut self, import_names: &str) -> Self {
+        self.import_names.push(import_names.to_string());
+        self
+    }
+
+    // A comma separated list of recipes to use instead of the defaults for
+    // the items they make.
+    pub fn recipes(mut self, recipe_names: &str) -> Self {
+        self.recipe_names.push(recipe_names.to_string());
+        self
+    }
+
+    pub fn assembling_machine_level(mut self, assembling_machine_level: AssemblingMachineLevel) -> Self {
+        self.settings.assembling_machine_level = assembling_machine_level;
+        self
+    }
+
+    pub fn furnace_level(mut self, furnace_level: FurnaceLevel) -> Self {
+        self.settings.furnace_level = furnace_level;
+        self
+    }
+
+    pub fn whole_numbers(mut self, whole_numbers: bool) -> Self {
+        self.settings.whole_numbers = whole_numbers;
+        self
+    }
+
+    pub fn calculate(self) -> Result<Calculation<'a>, CalculatorError> {
+        let material_database = self.material_database;
+        let mut settings = self.settings;
+        for import_names in &self.import_names {
+            let imports = material_database
+                .find_materials(import_names, &self.aliases)
+                .map_err(CalculatorError::UnknownItem)?;
+            settings
+                .imports
+                .extend(imports.into_iter().map(|material| material.name.clone()));
+        }
+        for recipe_names in &self.recipe_names {
+            let recipes = material_database
+                .find_recipes(recipe_names)
+                .map_err(CalculatorError::UnknownRecipe)?;
+            for recipe in recipes {
+                settings.choose_recipe(recipe, material_database);
+            }
+        }
+        let mut targets: Vec<(&Material, Fraction)> = Vec::new();
+        for item_names in &self.item_names {
+            targets.extend(parse_targets(material_database, &self.aliases, item_names, self.rate)?);
+        }
+
+        let total_raw = material_database
+            .total_raw_result(&targets, &settings)
+            .map_err(CalculatorError::Unsolvable)?;
+        let (ratio, total_raw) = match settings.whole_numbers {
+            true => total_raw
+                .whole_ratio(settings.ignore_furnaces_for_ratio, settings.ignore_raw_for_ratio)
+                .map_err(CalculatorError::Unsolvable)?,
+            false => (Fraction::from(1), total_raw),
+        };
+
+        let mut producers: Vec<(&Recipe, Fraction)> = total_raw.producers.into_iter().collect();
+        producers.sort_by(|(recipe_a, _), (recipe_b, _)| recipe_a.name.cmp(&recipe_b.name));
+        let extractors = total_raw.extractors;
+        let mut raw_requirements: Vec<RawRequirement> = total_raw
+            .total_raw
+            .into_iter()
+            .map(|(material, amount)| (material, amount, extractors.get(material).copied()))
+            .collect();
+        raw_requirements.sort_by(|(material_a, _, _), (material_b, _, _)| material_a.name.cmp(&material_b.name));
+        let mut surplus: Vec<(&Material, Fraction)> = total_raw.surplus.into_iter().collect();
+        surplus.sort_by(|(material_a, _), (material_b, _)| material_a.name.cmp(&material_b.name));
+        let mut item_rates: Vec<(&Material, Fraction)> = total_raw
+            .item_rates
+            .into_iter()
+            .filter(|(material, _rate)| !material.fluid)
+            .collect();
+        item_rates.sort_by(|(material_a, _), (material_b, _)| material_a.name.cmp(&material_b.name));
+
+        let extractor_counts: Vec<(Extractor, Fraction)> = raw_requirements
+            .iter()
+            .filter_map(|(_material, _amount, extractor)| *extractor)
+            .collect();
+        let power = estimate_power(&settings, &producers, &extractor_counts)
+            .ok_or(CalculatorError::Unsolvable(SolverError::TooLarge))?;
+        let pollution = estimate_pollution(&settings, &producers, &extractor_counts)
+            .ok_or(CalculatorError::Unsolvable(SolverError::TooLarge))?;
+
+        Ok(Calculation {
+            material_database,
+            settings,
+            targets,
+            ratio,
+            producers,
+            total_raw: raw_requirements,
+            surplus,
+            item_rates,
+            tree: total_raw.tree,
+            power,
+            pollution,
+            rounded: total_raw.rounded,
+        })
+    }
+}
+
+// Everything a calculation found, sorted by name. Rates are per second and
+// machine counts already include the whole number ratio, if one was asked for.
+pub struct Calculation<'a> {
+    material_database: &'a MaterialDatabase,
+    pub settings: UserSettings,
+    // The requested rates, before the whole number ratio.
+    pub targets: Vec<(&'a Material, Fraction)>,
+    pub ratio: Fraction,
+    pub producers: Vec<(&'a Recipe, Fraction)>,
+    pub total_raw: Vec<RawRequirement<'a>>,
+    pub surplus: Vec<(&'a Material, Fraction)>,
+    // Solid items moved between machines, for sizing belts.
+    pub item_rates: Vec<(&'a Material, Fraction)>,
+    pub tree: Vec<ProductionNode<'a>>,
+    pub power: PowerEstimate,
+    pub pollution: PollutionEstimate,
+    pub rounded: bool,
+}
+impl<'a> Calculation<'a> {
+    pub fn material_database(&self) -> &'a MaterialDatabase {
+        self.material_database
+    }
+}
+
+// A trailing number is only taken as the rate when the whole text isn't
+// already an item name or alias, so "assembling machine 2" still works.
+fn parse_targets<'a>(
+    material_database: &'a MaterialDatabase,
+    aliases: &HashMap<String, String>,
+    item_names: &str,
+    default_rate: Fraction,
+) -> Result<Vec<(&'a Material, Fraction)>, CalculatorError> {
+    let mut targets: Vec<(&Material, Fraction)> = Vec::new();
+    for target in item_names.split(',').map(|target| target.trim()) {
+        if let Some(material) = material_database.resolve_material(target, aliases) {
+            targets.push((material, default_rate));
+            continue;
+        }
+        let (name, rate) = match target.rsplit_once(' ') {
+            Some((name, rate)) if rate.starts_with(|c: char| c.is_ascii_digit() || c == '.') => {
+                (name.trim(), parse_rate(rate).map_err(CalculatorError::InvalidRate)?)
+            }
+            _ => (target, default_rate),
+        };
+        let material = material_database
+            .lookup_material(name, aliases)
+            .map_err(CalculatorError::UnknownItem)?;
+        targets.push((material, rate));
+    }
+    Ok(targets)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn producers<'a>(calculation: &Calculation<'a>) -> Vec<(&'a str, Fraction)> {
+        calculation
+            .producers
+            .iter()
+            .map(|(recipe, machines)| (recipe.name.as_str(), *machines))
+            .collect()
+    }
+
+    fn total_raw<'a>(calculation: &Calculation<'a>) -> Vec<(&'a str, Fraction)> {
+        calculation
+            .total_raw
+            .iter()
+            .map(|(material, amount, _extractor)| (material.name.as_str(), *amount))
+            .collect()
+    }
+
+    fn fraction(numerator: u64, denominator: u64) -> Fraction {
+        Fraction::new(numerator, denominator)
+    }
+
+    #[test]
+    fn calculates_logistic_science() {
+        let material_database = MaterialDatabase::built_in().unwrap();
+        let calculation = Calculator::new(&material_database)
+            .items("logistic science pack")
+            .calculate()
+            .unwrap();
+        assert_eq!(
+            producers(&calculation),
+            vec![
+                ("Copper Cable", fraction(3, 2)),
+                ("Copper Plate", fraction(24, 5)),
+                ("Electronic Circuit", fraction(1, 1)),
+                ("Inserter", fraction(1, 1)),
+                ("Iron Gear Wheel", fraction(3, 2)),
+                ("Iron Plate", fraction(88, 5)),
+                ("Logistic Science Pack", fraction(12, 1)),
+                ("Transport Belt", fraction(1, 2)),
+            ]
+        );
+        assert_eq!(
+            total_raw(&calculation),
+            vec![("Copper Ore", fraction(3, 2)), ("Iron Ore", fraction(11, 2))]
+        );
+        assert!(calculation.surplus.is_empty());
+    }
+
+    #[test]
+    fn calculates_plastic_with_cracking() {
+        let material_database = MaterialDatabase::built_in().unwrap();
+        let calculation = Calculator::new(&material_database)
+            .items("plastic bar")
+            .calculate()
+            .unwrap();
+        assert_eq!(
+            producers(&calculation),
+            vec![
+                ("Advanced Oil Processing", fraction(20, 39)),
+                ("Heavy Oil Cracking", fraction(5, 39)),
+                ("Light Oil Cracking", fraction(17, 39)),
+                ("Plastic Bar", fraction(1, 2)),
+            ]
+        );
+        assert_eq!(
+            total_raw(&calculation),
+            vec![
+                ("Coal", fraction(1, 2)),
+                ("Crude Oil", fraction(400, 39)),
+                ("Water", fraction(530, 39)),
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_unknown_items_and_bad_rates() {
+        let material_database = MaterialDatabase::built_in().unwrap();
+        let unknown = Calculator::new(&material_database).items("unobtainium").calculate();
+        assert!(matches!(unknown, Err(CalculatorError::UnknownItem(_))));
+        let bad_rate = Calculator::new(&material_database).items("iron plate 1/0").calculate();
+        assert!(matches!(bad_rate, Err(CalculatorError::InvalidRate(_))));
+    }
+}
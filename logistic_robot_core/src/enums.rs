@@ -0,0 +1,230 @@
+use core::fmt::Error;
+use core::fmt::Formatter;
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ProducerType {
+    AssemblingMachine,
+    Furnace,
+    ChemicalPlant,
+    RocketSilo,
+    OilRefinery,
+    Centrifuge,
+}
+impl Display for ProducerType {
+    fn fmt(&self, formatter: &mut Formatter) -> Result<(), Error> {
+        write!(
+            formatter,
+            "{}",
+            match self {
+                ProducerType::AssemblingMachine => "Assembling Machine",
+                ProducerType::Furnace => "Furnace",
+                ProducerType::ChemicalPlant => "Chemical Plant",
+                ProducerType::RocketSilo => "Rocket Silo",
+                ProducerType::OilRefinery => "Oil Refinery",
+                ProducerType::Centrifuge => "Centrifuge",
+            }
+        )
+    }
+}
+
+#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
+pub enum AssemblingMachineLevel {
+    One,
+    Two,
+    Three,
+}
+impl Display for AssemblingMachineLevel {
+    fn fmt(&self, formatter: &mut Formatter) -> Result<(), Error> {
+        write!(
+            formatter,
+            "{}",
+            match self {
+                AssemblingMachineLevel::One => "1",
+                AssemblingMachineLevel::Two => "2",
+                AssemblingMachineLevel::Three => "3",
+            }
+        )
+    }
+}
+
+#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
+pub enum FurnaceLevel {
+    Stone,
+    Steel,
+    Electric,
+}
+impl Display for FurnaceLevel {
+    fn fmt(&self, formatter: &mut Formatter) -> Result<(), Error> {
+        write!(
+            formatter,
+            "{}",
+            match self {
+                FurnaceLevel::Stone => "Stone",
+                FurnaceLevel::Steel => "Steel",
+                FurnaceLevel::Electric => "Electric",
+            }
+        )
+    }
+}
+
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Module {
+    Speed1,
+    Speed2,
+    Speed3,
+    Productivity1,
+    Productivity2,
+    Productivity3,
+    Efficiency1,
+    Efficiency2,
+    Efficiency3,
+}
+impl Display for Module {
+    fn fmt(&self, formatter: &mut Formatter) -> Result<(), Error> {
+        write!(
+            formatter,
+            "{}",
+            match self {
+                Module::Speed1 => "S1",
+                Module::Speed2 => "S2",
+                Module::Speed3 => "S3",
+                Module::Productivity1 => "P1",
+                Module::Productivity2 => "P2",
+                Module::Productivity3 => "P3",
+                Module::Efficiency1 => "E1",
+                Module::Efficiency2 => "E2",
+                Module::Efficiency3 => "E3",
+            }
+        )
+    }
+}
+
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
+pub enum ExtractorType {
+    MiningDrill,
+    Pumpjack,
+    OffshorePump,
+}
+impl Display for ExtractorType {
+    fn fmt(&self, formatter: &mut Formatter) -> Result<(), Error> {
+        write!(
+            formatter,
+            "{}",
+            match self {
+                ExtractorType::MiningDrill => "Mining Drill",
+                ExtractorType::Pumpjack => "Pumpjack",
+                ExtractorType::OffshorePump => "Offshore Pump",
+            }
+        )
+    }
+}
+
+#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
+pub enum MiningDrillLevel {
+    Burner,
+    Electric,
+}
+impl Display for MiningDrillLevel {
+    fn fmt(&self, formatter: &mut Formatter) -> Result<(), Error> {
+        write!(
+            formatter,
+            "{}",
+            match self {
+                MiningDrillLevel::Burner => "Burner",
+                MiningDrillLevel::Electric => "Electric",
+            }
+        )
+    }
+}
+
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
+pub enum Extractor {
+    BurnerMiningDrill,
+    ElectricMiningDrill,
+    Pumpjack,
+    OffshorePump,
+}
+impl Display for Extractor {
+    fn fmt(&self, formatter: &mut Formatter) -> Result<(), Error> {
+        write!(
+            formatter,
+            "{}",
+            match self {
+                Extractor::BurnerMiningDrill => "Burner Mining Drill",
+                Extractor::ElectricMiningDrill => "Electric Mining Drill",
+                Extractor::Pumpjack => "Pumpjack",
+                Extractor::OffshorePump => "Offshore Pump",
+            }
+        )
+    }
+}
+
+#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
+pub enum BeltTier {
+    Yellow,
+    Red,
+    Blue,
+}
+impl Display for BeltTier {
+    fn fmt(&self, formatter: &mut Formatter) -> Result<(), Error> {
+        write!(
+            formatter,
+            "{}",
+            match self {
+                BeltTier::Yellow => "Yellow",
+                BeltTier::Red => "Red",
+                BeltTier::Blue => "Blue",
+            }
+        )
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TimeUnit {
+    Second,
+    Minute,
+    Hour,
+}
+impl Display for TimeUnit {
+    fn fmt(&self, formatter: &mut Formatter) -> Result<(), Error> {
+        write!(
+            formatter,
+            "{}",
+            match self {
+                TimeUnit::Second => "Second",
+                TimeUnit::Minute => "Minute",
+                TimeUnit::Hour => "Hour",
+            }
+        )
+    }
+}
+
+// Parse the names users type for machine tiers, ignoring case.
+pub fn parse_assembling_machine_level(level: &str) -> Option<AssemblingMachineLevel> {
+    match &*level.to_lowercase() {
+        "1" => Some(AssemblingMachineLevel::One),
+        "2" => Some(AssemblingMachineLevel::Two),
+        "3" => Some(AssemblingMachineLevel::Three),
+        _ => None,
+    }
+}
+
+pub fn parse_furnace_level(level: &str) -> Option<FurnaceLevel> {
+    match &*level.to_lowercase() {
+        "stone" => Some(FurnaceLevel::Stone),
+        "steel" => Some(FurnaceLevel::Steel),
+        "electric" => Some(FurnaceLevel::Electric),
+        _ => None,
+    }
+}
+
+pub fn parse_belt_tier(belt_tier: &str) -> Option<BeltTier> {
+    match &*belt_tier.to_lowercase() {
+        "yellow" | "transport" => Some(BeltTier::Yellow),
+        "red" | "fast" => Some(BeltTier::Red),
+        "blue" | "express" => Some(BeltTier::Blue),
+        _ => None,
+    }
+}
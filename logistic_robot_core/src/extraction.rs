@@ -0,0 +1,96 @@
+use crate::enums::{Extractor, ExtractorType, MiningDrillLevel};
+use crate::user_settings::UserSettings;
+use fraction::{CheckedDiv, CheckedMul, Fraction};
+
+const BURNER_MINING_DRILL_SPEED: (u64, u64) = (1, 4);
+const ELECTRIC_MINING_DRILL_SPEED: (u64, u64) = (1, 2);
+// A pumpjack on a 100% yield field pumps 10 units per second.
+const PUMPJACK_BASE_OUTPUT: u64 = 10;
+const OFFSHORE_PUMP_OUTPUT: u64 = 1200;
+// Each level of mining productivity research adds 10%.
+const MINING_PRODUCTIVITY_PER_LEVEL: (u64, u64) = (1, 10);
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct Extraction {
+    pub extractor_type: ExtractorType,
+    pub mining_time: Fraction,
+    pub requires_fluid: bool,
+}
+impl Extraction {
+    pub fn new(extractor_type: ExtractorType, mining_time: Fraction, requires_fluid: bool) -> Self {
+        Extraction {
+            extractor_type,
+            mining_time,
+            requires_fluid,
+        }
+    }
+
+    // Burner drills can't be fed a mining fluid, so resources like uranium
+    // ore always use electric drills.
+    pub fn extractor(&self, user_settings: &UserSettings) -> Extractor {
+        match self.extractor_type {
+            ExtractorType::MiningDrill => match user_settings.mining_drill_level {
+                MiningDrillLevel::Burner if !self.requires_fluid => Extractor::BurnerMiningDrill,
+                _ => Extractor::ElectricMiningDrill,
+            },
+            ExtractorType::Pumpjack => Extractor::Pumpjack,
+            ExtractorType::OffshorePump => Extractor::OffshorePump,
+        }
+    }
+
+    // None when the settings make the output too large to hold.
+    pub fn output_per_extractor(&self, user_settings: &UserSettings) -> Option<Fraction> {
+        let mining_productivity = Fraction::from(1)
+            + Fraction::new(MINING_PRODUCTIVITY_PER_LEVEL.0, MINING_PRODUCTIVITY_PER_LEVEL.1)
+                * Fraction::from(user_settings.mining_productivity_level);
+        match self.extractor(user_settings) {
+            Extractor::BurnerMiningDrill => {
+                Fraction::new(BURNER_MINING_DRILL_SPEED.0, BURNER_MINING_DRILL_SPEED.1)
+                    .checked_mul(&mining_productivity)?
+                    .checked_div(&self.mining_time)
+            }
+            Extractor::ElectricMiningDrill => {
+                Fraction::new(ELECTRIC_MINING_DRILL_SPEED.0, ELECTRIC_MINING_DRILL_SPEED.1)
+                    .checked_mul(&mining_productivity)?
+                    .checked_div(&self.mining_time)
+            }
+            Extractor::Pumpjack => Fraction::from(PUMPJACK_BASE_OUTPUT)
+                .checked_mul(&Fraction::new(user_settings.pumpjack_yield, 100u64))?
+                .checked_mul(&mining_productivity),
+            Extractor::OffshorePump => Some(Fraction::from(OFFSHORE_PUMP_OUTPUT)),
+        }
+    }
+
+    // None when the count is too large to hold.
+    pub fn extractor_count(&self, rate: Fraction, user_settings: &UserSettings) -> Option<(Extractor, Fraction)> {
+        let count = rate.checked_div(&self.output_per_extractor(user_settings)?)?;
+        Some((self.extractor(user_settings), count))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_extractors() {
+        let user_settings = UserSettings::default();
+        let iron_ore = Extraction::new(ExtractorType::MiningDrill, Fraction::from(1), false);
+        assert_eq!(
+            iron_ore.extractor_count(Fraction::from(3), &user_settings),
+            Some((Extractor::ElectricMiningDrill, Fraction::from(6)))
+        );
+    }
+
+    #[test]
+    fn refuses_counts_too_large_to_hold() {
+        let user_settings = UserSettings {
+            pumpjack_yield: u64::MAX,
+            mining_productivity_level: u32::MAX,
+            ..UserSettings::default()
+        };
+        let crude_oil = Extraction::new(ExtractorType::Pumpjack, Fraction::from(1), false);
+        assert_eq!(crude_oil.output_per_extractor(&user_settings), None);
+        assert_eq!(crude_oil.extractor_count(Fraction::from(10), &user_settings), None);
+    }
+}
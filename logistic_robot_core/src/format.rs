@@ -0,0 +1,479 @@
+use crate::calculator::Calculation;
+use crate::enums::ProducerType;
+use crate::materials::material::Material;
+use crate::modules::module_slots;
+use crate::recipe::Recipe;
+use crate::total_raw_result::ProductionNode;
+use crate::user_settings::UserSettings;
+use fraction::{Fraction, ToPrimitive};
+
+// Text for each part of a calculation, in Markdown with the numbers in bold,
+// and rates in the settings' display unit.
+
+pub fn title(calculation: &Calculation) -> String {
+    let display_unit = calculation.settings.display_unit;
+    format!(
+        "Recipe for {} per {}:",
+        calculation
+            .targets
+            .iter()
+            .map(|(material, rate)| {
+                format!(
+                    "{} {}",
+                    print_fraction(*rate * calculation.ratio * display_unit.seconds()),
+                    material.name
+                )
+            })
+            .collect::<Vec<String>>()
+            .join(", "),
+        display_unit.to_string().to_lowercase()
+    )
+}
+
+pub fn description(calculation: &Calculation) -> String {
+    let display_unit = calculation.settings.display_unit;
+    let mut description_vec = calculation
+        .producers
+        .iter()
+        .map(|(recipe, amount)| {
+            format!(
+                "{}: **{}** {} required, {}.",
+                recipe.name,
+                print_fraction(*amount),
+                producer_type_name(recipe.producer_type, *amount != Fraction::from(1)),
+                output_rates(calculation, recipe, *amount)
+            )
+        })
+        .collect::<Vec<String>>();
+    description_vec.extend(vec!["".to_string()]);
+    description_vec.extend(
+        calculation
+            .total_raw
+            .iter()
+            .map(|(material, amount, extractor)| match extractor {
+                Some((extractor, extractor_amount)) => format!(
+                    "{}: **{}**{} required (**{}** {}{}).",
+                    material.name,
+                    print_fraction(*amount * display_unit.seconds()),
+                    display_unit.suffix(),
+                    print_fraction(*extractor_amount),
+                    extractor,
+                    if *extractor_amount == Fraction::from(1) { "" } else { "s" }
+                ),
+                None => format!(
+                    "{}: **{}**{} required.",
+                    material.name,
+                    print_fraction(*amount * display_unit.seconds()),
+                    display_unit.suffix()
+                ),
+            })
+            .collect::<Vec<String>>(),
+    );
+    if !calculation.surplus.is_empty() {
+        description_vec.extend(vec!["".to_string()]);
+        description_vec.extend(calculation.surplus.iter().map(|(material, amount)| {
+            format!(
+                "Surplus {}: **{}**{}.",
+                material.name,
+                print_fraction(*amount * display_unit.seconds()),
+                display_unit.suffix()
+            )
+        }));
+    }
+    description_vec.join("\n")
+}
+
+// One line per item in the breakdown, indented by depth, stopping at the
+// settings' tree depth.
+pub fn tree_lines(calculation: &Calculation) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for node in &calculation.tree {
+        add_tree_lines(&calculation.settings, node, 0, &mut lines);
+    }
+    lines
+}
+
+fn add_tree_lines(
+    user_settings: &UserSettings,
+    node: &ProductionNode,
+    depth: u32,
+    lines: &mut Vec<String>,
+) {
+    let display_unit = user_settings.display_unit;
+    let producers = match node.producers.as_slice() {
+        [] => String::new(),
+        [(recipe, machines)] if recipe.name == node.material.name => format!(
+            ": **{}** {}",
+            print_fraction(*machines),
+            producer_type_name(recipe.producer_type, *machines != Fraction::from(1))
+        ),
+        producers => format!(
+            ": {}",
+            producers
+                .iter()
+                .map(|(recipe, machines)| {
+                    format!(
+                        "**{}** {} ({})",
+                        print_fraction(*machines),
+                        producer_type_name(recipe.producer_type, *machines != Fraction::from(1)),
+                        recipe.name
+                    )
+                })
+                .collect::<Vec<String>>()
+                .join(", ")
+        ),
+    };
+    let collapsed = depth >= user_settings.tree_depth && !node.children.is_empty();
+    lines.push(format!(
+        "{}{}{} **{}**{}{}{}",
+        "\u{2003}".repeat(depth as usize),
+        if depth > 0 { "↳ " } else { "" },
+        node.material.name,
+        print_fraction(node.rate * display_unit.seconds()),
+        display_unit.suffix(),
+        producers,
+        if node.repeated {
+            " (made further up this branch)"
+        } else if collapsed {
+            " …"
+        } else {
+            ""
+        }
+    ));
+    if !collapsed {
+        for child in &node.children {
+            add_tree_lines(user_settings, child, depth + 1, lines);
+        }
+    }
+}
+
+fn output_rates(calculation: &Calculation, recipe: &Recipe, machines: Fraction) -> String {
+    let material_database = calculation.material_database();
+    let display_unit = calculation.settings.display_unit;
+    let output_rates = Material::calculate_output_rates(&calculation.settings, recipe, machines);
+    match output_rates.as_slice() {
+        [(material_index, rate)] if material_database.material(*material_index).name == recipe.name => {
+            format!(
+                "**{}**{}",
+                print_fraction(*rate * display_unit.seconds()),
+                display_unit.suffix()
+            )
+        }
+        _ => output_rates
+            .iter()
+            .map(|(material_index, rate)| {
+                format!(
+                    "{} **{}**{}",
+                    material_database.material(*material_index).name,
+                    print_fraction(*rate * display_unit.seconds()),
+                    display_unit.suffix()
+                )
+            })
+            .collect::<Vec<String>>()
+            .join(", "),
+    }
+}
+
+// The modules in use for each machine type, then any recipe specific ones.
+// None when no machine has modules.
+pub fn module_description(calculation: &Calculation) -> Option<String> {
+    let user_settings = &calculation.settings;
+    let mut producer_types: Vec<ProducerType> = Vec::new();
+    let mut overrides: Vec<String> = Vec::new();
+    for (recipe, _amount) in &calculation.producers {
+        if user_settings
+            .module_overrides
+            .contains_key(&recipe.name.to_lowercase())
+        {
+            let module_configuration = user_settings.module_configuration(recipe);
+            overrides.push(format!("{}: {}", recipe.name, module_configuration));
+        } else if !producer_types.contains(&recipe.producer_type) {
+            producer_types.push(recipe.producer_type);
+        }
+    }
+    let mut description_vec: Vec<String> = producer_types
+        .into_iter()
+        .filter_map(|producer_type| {
+            let slots = module_slots(
+                &producer_type,
+                &user_settings.assembling_machine_level,
+                &user_settings.furnace_level,
+            );
+            user_settings
+                .module_configurations
+                .get(&producer_type)
+                .map(|module_configuration| module_configuration.limited_to(slots, true))
+                .filter(|module_configuration| !module_configuration.is_empty())
+                .map(|module_configuration| {
+                    format!(
+                        "{}: {}",
+                        producer_type_name(producer_type, true),
+                        module_configuration
+                    )
+                })
+        })
+        .collect();
+    description_vec.sort();
+    description_vec.extend(overrides);
+    match description_vec.is_empty() {
+        true => None,
+        false => Some(description_vec.join("\n")),
+    }
+}
+
+// Modules that were set for a machine but couldn't be used, either for lack
+// of slots or because the recipe doesn't take productivity modules. Recipes
+// missing the same modules for the same reason share a line. None when every
+// module fit.
+pub fn module_warnings(calculation: &Calculation) -> Option<String> {
+    let user_settings = &calculation.settings;
+    let mut warnings: Vec<(String, String, Vec<&str>)> = Vec::new();
+    for (recipe, _amount) in &calculation.producers {
+        let (_module_configuration, left_out) = user_settings.fitted_module_configuration(recipe);
+        if left_out.is_empty() {
+            continue;
+        }
+        let slots = module_slots(
+            &recipe.producer_type,
+            &user_settings.assembling_machine_level_for(recipe),
+            &user_settings.furnace_level_for(recipe),
+        );
+        let mut reasons: Vec<String> = Vec::new();
+        if !recipe.allow_productivity && left_out.iter().any(|module| module.productivity() > Fraction::from(0)) {
+            reasons.push("no productivity modules allowed".to_string());
+        }
+        if left_out.iter().any(|module| recipe.allow_productivity || module.productivity() == Fraction::from(0)) {
+            reasons.push(match slots {
+                0 => "no module slots".to_string(),
+                1 => "only 1 module slot".to_string(),
+                slots => format!("only {} module slots", slots),
+            });
+        }
+        let modules = left_out
+            .iter()
+            .map(|module| module.to_string())
+            .collect::<Vec<String>>()
+            .join(", ");
+        let reason = reasons.join(" and ");
+        match warnings
+            .iter_mut()
+            .find(|(existing_modules, existing_reason, _recipe_names)| {
+                *existing_modules == modules && *existing_reason == reason
+            }) {
+            Some((_modules, _reason, recipe_names)) => recipe_names.push(&recipe.name),
+            None => warnings.push((modules, reason, vec![&recipe.name])),
+        }
+    }
+    match warnings.is_empty() {
+        true => None,
+        false => Some(
+            warnings
+                .into_iter()
+                .map(|(modules, reason, recipe_names)| {
+                    format!("{} left out of {}: {}", modules, recipe_names.join(", "), reason)
+                })
+                .collect::<Vec<String>>()
+                .join("\n"),
+        ),
+    }
+}
+
+pub fn power_description(calculation: &Calculation) -> String {
+    let power_estimate = &calculation.power;
+    let display_unit = calculation.settings.display_unit;
+    let mut description_vec: Vec<String> = power_estimate
+        .producers
+        .iter()
+        .map(|(producer_type, power)| {
+            format!(
+                "{}: **{}**",
+                producer_type_name(*producer_type, true),
+                print_megawatts(*power)
+            )
+        })
+        .chain(power_estimate.extractors.iter().map(|(extractor, power)| {
+            format!("{}s: **{}**", extractor, print_megawatts(*power))
+        }))
+        .collect();
+    description_vec.sort();
+    if !power_estimate.producers.is_empty() || !power_estimate.extractors.is_empty() {
+        description_vec.push(format!(
+            "Total: **{}** ({} idle drain)",
+            print_megawatts(power_estimate.total()),
+            print_megawatts(power_estimate.drain)
+        ));
+    }
+    if power_estimate.coal != Fraction::from(0) {
+        description_vec.push(format!(
+            "Coal for burner machines: **{}**{}",
+            print_fraction(power_estimate.coal * display_unit.seconds()),
+            display_unit.suffix()
+        ));
+    }
+    description_vec.join("\n")
+}
+
+pub fn belt_lines(calculation: &Calculation) -> Vec<String> {
+    let belt_tier = calculation.settings.belt_tier;
+    calculation
+        .item_rates
+        .iter()
+        .map(|(material, rate)| {
+            // Pluralized by the printed number, so a rate just off a full
+            // belt doesn't read "1.00 Yellow Belts".
+            let belts = print_decimal(belt_tier.belts_required(*rate));
+            format!(
+                "{}: **{}** {} Belt{} (**{}** lanes)",
+                material.name,
+                belts,
+                belt_tier,
+                if belts == "1.00" { "" } else { "s" },
+                print_decimal(belt_tier.lanes_required(*rate))
+            )
+        })
+        .collect()
+}
+
+pub fn pollution_description(calculation: &Calculation) -> String {
+    let pollution_estimate = &calculation.pollution;
+    let mut description_vec: Vec<String> = pollution_estimate
+        .producers
+        .iter()
+        .map(|(producer_type, pollution)| {
+            format!(
+                "{}: **{}**",
+                producer_type_name(*producer_type, true),
+                print_decimal(*pollution)
+            )
+        })
+        .chain(pollution_estimate.extractors.iter().map(|(extractor, pollution)| {
+            format!("{}s: **{}**", extractor, print_decimal(*pollution))
+        }))
+        .collect();
+    description_vec.sort();
+    description_vec.push(format!(
+        "Total: **{}** per minute",
+        print_decimal(pollution_estimate.total())
+    ));
+    description_vec.join("\n")
+}
+
+// The machine tiers used, then any per recipe overrides, imports and a note
+// when values had to be rounded.
+pub fn footer(calculation: &Calculation) -> String {
+    let user_settings = &calculation.settings;
+    let overrides_used: Vec<String> = calculation
+        .producers
+        .iter()
+        .filter_map(|(recipe, _machines)| {
+            user_settings
+                .level_override(recipe)
+                .map(|level_override| format!("{}: {}", recipe.name, level_override))
+        })
+        .collect();
+    format!(
+        "Using Assembling Machine {} and {} Furnace{}{}{}",
+        user_settings.assembling_machine_level,
+        user_settings.furnace_level,
+        match overrides_used.is_empty() {
+            true => "".to_string(),
+            false => format!("\nOverrides: {}", overrides_used.join(", ")),
+        },
+        match user_settings.imports.is_empty() {
+            true => "".to_string(),
+            false => format!("\nImported: {}", user_settings.imports.join(", ")),
+        },
+        if calculation.rounded { "\nSome values were rounded." } else { "" }
+    )
+}
+
+pub fn producer_type_name(producer_type: ProducerType, plural: bool) -> String {
+    match (producer_type, plural) {
+        (ProducerType::AssemblingMachine, false) => "Assembling Machine".to_string(),
+        (ProducerType::AssemblingMachine, true) => "Assembling Machines".to_string(),
+        (ProducerType::ChemicalPlant, false) => "Chemical Plant".to_string(),
+        (ProducerType::ChemicalPlant, true) => "Chemical Plants".to_string(),
+        (ProducerType::Furnace, false) => "Furnace".to_string(),
+        (ProducerType::Furnace, true) => "Furnaces".to_string(),
+        (ProducerType::RocketSilo, false) => "Rocket Silo".to_string(),
+        (ProducerType::RocketSilo, true) => "Rocket Silos".to_string(),
+        (ProducerType::OilRefinery, false) => "Oil Refinery".to_string(),
+        (ProducerType::OilRefinery, true) => "Oil Refineries".to_string(),
+        (ProducerType::Centrifuge, false) => "Centrifuge".to_string(),
+        (ProducerType::Centrifuge, true) => "Centrifuges".to_string(),
+    }
+}
+
+// Whole part and remainder, e.g. "2 1/3".
+pub fn print_fraction(fraction: Fraction) -> String {
+    let fract = fraction.fract();
+    if fract.numer().unwrap_or(&0) == &0 || fraction.trunc() == Fraction::from(0) {
+        fraction.to_string()
+    } else {
+        format!("{} {}", fraction.trunc(), fract)
+    }
+}
+
+pub fn print_megawatts(kilowatts: Fraction) -> String {
+    format!("{} MW", print_decimal(kilowatts / Fraction::from(1000)))
+}
+
+pub fn print_decimal(fraction: Fraction) -> String {
+    format!("{:.2}", fraction.to_f64().unwrap_or(0.0))
+}
+
+// Recipe amounts and times, to three places without trailing zeros, so 16/5
+// reads "3.2" and 7/1000 reads "0.007".
+pub fn print_amount(fraction: Fraction) -> String {
+    let decimal = format!("{:.3}", fraction.to_f64().unwrap_or(0.0));
+    decimal.trim_end_matches('0').trim_end_matches('.').to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::materials::material_database::MaterialDatabase;
+    use crate::Calculator;
+
+    #[test]
+    fn prints_amounts_without_trailing_zeros() {
+        assert_eq!(print_amount(Fraction::new(16u64, 5u64)), "3.2");
+        assert_eq!(print_amount(Fraction::new(7u64, 1000u64)), "0.007");
+        assert_eq!(print_amount(Fraction::from(40)), "40");
+    }
+
+    #[test]
+    fn collapses_branches_below_the_tree_depth() {
+        let material_database = MaterialDatabase::built_in().unwrap();
+        let calculation = Calculator::new(&material_database)
+            .settings(UserSettings { tree_depth: 2, ..UserSettings::default() })
+            .items("logistic science pack")
+            .calculate()
+            .unwrap();
+        assert_eq!(
+            tree_lines(&calculation),
+            vec![
+                "Logistic Science Pack **1**/s: **12** Assembling Machines",
+                "\u{2003}↳ Inserter **1**/s: **1** Assembling Machine",
+                "\u{2003}\u{2003}↳ Electronic Circuit **1**/s: **1** Assembling Machine …",
+                "\u{2003}\u{2003}↳ Iron Gear Wheel **1**/s: **1** Assembling Machine …",
+                "\u{2003}\u{2003}↳ Iron Plate **1**/s: **3 1/5** Furnaces …",
+                "\u{2003}↳ Transport Belt **1**/s: **1/2** Assembling Machines",
+                "\u{2003}\u{2003}↳ Iron Gear Wheel **1/2**/s: **1/2** Assembling Machines …",
+                "\u{2003}\u{2003}↳ Iron Plate **1/2**/s: **1 3/5** Furnaces …",
+            ]
+        );
+    }
+
+    #[test]
+    fn marks_materials_made_further_up_the_branch() {
+        let material_database = MaterialDatabase::built_in().unwrap();
+        let calculation = Calculator::new(&material_database).items("uranium-235").calculate().unwrap();
+        let lines = tree_lines(&calculation);
+        assert_eq!(lines.len(), 7);
+        assert_eq!(
+            lines.iter().filter(|line| line.ends_with(" (made further up this branch)")).count(),
+            3
+        );
+    }
+}
@@ -0,0 +1,22 @@
+// The calculator behind the bot, free of anything Discord: loading the item
+// and recipe data, solving for the machines and raw materials a target needs,
+// and formatting the results. Most callers only need Calculator.
+pub mod belts;
+pub mod calculator;
+pub mod enums;
+pub mod extraction;
+pub mod format;
+pub mod materials;
+pub mod modules;
+pub mod pollution;
+pub mod power;
+pub mod rate;
+pub mod recipe;
+pub mod report;
+pub mod solver;
+pub mod total_raw_result;
+pub mod user_settings;
+
+pub use calculator::{Calculation, Calculator, CalculatorError};
+pub use materials::material_database::MaterialDatabase;
+pub use user_settings::UserSettings;
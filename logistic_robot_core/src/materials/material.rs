@@ -0,0 +1,65 @@
+use crate::enums::{AssemblingMachineLevel, FurnaceLevel, ProducerType};
+use crate::extraction::Extraction;
+use crate::recipe::Recipe;
+use crate::user_settings::*;
+use fraction::Fraction;
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct Material {
+    pub name: String,
+    pub recipes: Vec<usize>,
+    pub raw_cost: Fraction,
+    pub extraction: Option<Extraction>,
+    pub fluid: bool,
+    pub aliases: Vec<String>,
+}
+impl Material {
+    pub fn new(name: &str) -> Material {
+        Material {
+            name: name.to_string(),
+            recipes: Vec::new(),
+            raw_cost: Fraction::from(1),
+            extraction: None,
+            fluid: false,
+            aliases: Vec::new(),
+        }
+    }
+
+    pub fn calculate_production_modifier(user_settings: &UserSettings, recipe: &Recipe) -> Fraction {
+        let base_speed = match recipe.producer_type {
+            ProducerType::Furnace => match user_settings.furnace_level_for(recipe) {
+                FurnaceLevel::Stone => Fraction::from(1),
+                FurnaceLevel::Steel | FurnaceLevel::Electric => Fraction::from(2),
+            },
+            ProducerType::AssemblingMachine => match user_settings.assembling_machine_level_for(recipe) {
+                AssemblingMachineLevel::One => Fraction::from(0.5),
+                AssemblingMachineLevel::Two => Fraction::from(0.75),
+                AssemblingMachineLevel::Three => Fraction::from(1.25),
+            },
+            _ => Fraction::from(1),
+        };
+        base_speed * user_settings.module_configuration(recipe).speed_multiplier()
+    }
+
+    pub fn calculate_productivity_modifier(user_settings: &UserSettings, recipe: &Recipe) -> Fraction {
+        user_settings
+            .module_configuration(recipe)
+            .productivity_multiplier()
+    }
+
+    // Items per second of each product made by the given number of machines.
+    pub fn calculate_output_rates(
+        user_settings: &UserSettings,
+        recipe: &Recipe,
+        machines: Fraction,
+    ) -> Vec<(usize, Fraction)> {
+        let crafts = machines * Material::calculate_production_modifier(user_settings, recipe)
+            / recipe.time;
+        let productivity = Material::calculate_productivity_modifier(user_settings, recipe);
+        recipe
+            .products
+            .iter()
+            .map(|(material_index, amount)| (*material_index, crafts * *amount * productivity))
+            .collect()
+    }
+}
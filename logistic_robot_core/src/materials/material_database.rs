@@ -0,0 +1,215 @@
+use crate::materials::material::Material;
+use crate::materials::name_lookup::{lookup_by_name, search_by_name, NameLookupError};
+use crate::materials::materials_json_parser::{parse_materials_json, MaterialsParseError};
+use crate::recipe::Recipe;
+use crate::solver;
+use crate::solver::SolverError;
+use crate::total_raw_result::TotalRawResult;
+use crate::user_settings::UserSettings;
+use fraction::Fraction;
+use std::collections::HashMap;
+
+pub struct MaterialDatabase {
+    materials: Vec<Material>,
+    recipes: Vec<Recipe>,
+}
+
+impl MaterialDatabase {
+    pub fn new(materials_json: String) -> Result<Self, MaterialsParseError> {
+        let (materials, recipes) = parse_materials_json(materials_json)?;
+        Ok(MaterialDatabase { materials, recipes })
+    }
+
+    // The vanilla items and recipes shipped with this crate.
+    pub fn built_in() -> Result<Self, MaterialsParseError> {
+        MaterialDatabase::new(include_str!("materials.json").to_string())
+    }
+
+    pub fn find_material(&self, material_name: &str) -> Option<&Material> {
+        self.materials
+            .iter()
+            .find(|material| material.name.to_lowercase() == material_name.to_lowercase())
+    }
+
+    // Exact lookup that also accepts the server's aliases and those shipped
+    // with the data, checked in that order before the item names themselves.
+    pub fn resolve_material(
+        &self,
+        material_name: &str,
+        guild_aliases: &HashMap<String, String>,
+    ) -> Option<&Material> {
+        let material_name = material_name.trim().to_lowercase();
+        if let Some(material) = guild_aliases
+            .get(&material_name)
+            .and_then(|canonical_name| self.find_material(canonical_name))
+        {
+            return Some(material);
+        }
+        self.materials
+            .iter()
+            .find(|material| {
+                material
+                    .aliases
+                    .iter()
+                    .any(|alias| alias.to_lowercase() == material_name)
+            })
+            .or_else(|| self.find_material(&material_name))
+    }
+
+    // Like resolve_material, but tolerates typos and plurals in names and
+    // aliases alike, suggesting the closest items when there is no single
+    // obvious match.
+    pub fn lookup_material(
+        &self,
+        material_name: &str,
+        guild_aliases: &HashMap<String, String>,
+    ) -> Result<&Material, NameLookupError> {
+        if let Some(material) = self.resolve_material(material_name, guild_aliases) {
+            return Ok(material);
+        }
+        lookup_by_name("item", material_name, self.material_candidates(guild_aliases))
+    }
+
+    // Every name an item goes by, paired with its real name and the item.
+    fn material_candidates<'a: 'b, 'b>(
+        &'a self,
+        guild_aliases: &'b HashMap<String, String>,
+    ) -> impl Iterator<Item = (&'b str, &'b str, &'a Material)> + 'b {
+        let guild_alias_candidates = guild_aliases.iter().filter_map(move |(alias, canonical_name)| {
+            self.find_material(canonical_name)
+                .map(|material| (alias.as_str(), material.name.as_str(), material))
+        });
+        self.materials
+            .iter()
+            .flat_map(|material| {
+                std::iter::once(material.name.as_str())
+                    .chain(material.aliases.iter().map(|alias| alias.as_str()))
+                    .map(move |name| (name, material.name.as_str(), material))
+            })
+            .chain(guild_alias_candidates)
+    }
+
+    // Items whose names or aliases are close to the query, best match first.
+    pub fn search_materials(
+        &self,
+        query: &str,
+        guild_aliases: &HashMap<String, String>,
+        limit: usize,
+    ) -> Vec<&Material> {
+        search_by_name(query, self.material_candidates(guild_aliases), limit)
+    }
+
+    pub fn find_recipe(&self, recipe_name: &str) -> Option<&Recipe> {
+        self.recipes
+            .iter()
+            .find(|recipe| recipe.name.to_lowercase() == recipe_name.trim().to_lowercase())
+    }
+
+    // The recipe a material is made with: the one the user picked, otherwise
+    // the enabled recipe named after it, otherwise its first enabled recipe.
+    // None for raw materials.
+    pub fn producing_recipe(&self, material: &Material, user_settings: &UserSettings) -> Option<&Recipe> {
+        let recipes: Vec<&Recipe> = material.recipes.iter().map(|&index| self.recipe(index)).collect();
+        if let Some(choice) = user_settings.recipe_choice(material) {
+            if let Some(recipe) = recipes
+                .iter()
+                .find(|recipe| recipe.name.to_lowercase() == choice.to_lowercase())
+            {
+                return Some(recipe);
+            }
+        }
+        recipes
+            .iter()
+            .find(|recipe| recipe.enabled && recipe.name == material.name)
+            .or_else(|| recipes.iter().find(|recipe| recipe.enabled))
+            .or_else(|| recipes.first())
+            .copied()
+    }
+
+    pub fn lookup_recipe(&self, recipe_name: &str) -> Result<&Recipe, NameLookupError> {
+        lookup_by_name(
+            "recipe",
+            recipe_name,
+            self.recipes
+                .iter()
+                .map(|recipe| (recipe.name.as_str(), recipe.name.as_str(), recipe)),
+        )
+    }
+
+    // Looks up a comma separated list of names, failing with the first name
+    // that isn't a known material.
+    pub fn find_materials(
+        &self,
+        material_names: &str,
+        guild_aliases: &HashMap<String, String>,
+    ) -> Result<Vec<&Material>, NameLookupError> {
+        material_names
+            .split(',')
+            .map(|material_name| material_name.trim())
+            .filter(|material_name| !material_name.is_empty())
+            .map(|material_name| self.lookup_material(material_name, guild_aliases))
+            .collect()
+    }
+
+    pub fn find_recipes(&self, recipe_names: &str) -> Result<Vec<&Recipe>, NameLookupError> {
+        recipe_names
+            .split(',')
+            .map(|recipe_name| recipe_name.trim())
+            .filter(|recipe_name| !recipe_name.is_empty())
+            .map(|recipe_name| self.lookup_recipe(recipe_name))
+            .collect()
+    }
+
+    // Solves for every target at once, so intermediates shared between them
+    // are only built once.
+    pub fn total_raw_result<'a>(
+        &'a self,
+        targets: &[(&Material, Fraction)],
+        user_settings: &UserSettings,
+    ) -> Result<TotalRawResult<'a>, SolverError> {
+        let target_indices: Vec<(usize, Fraction)> = targets
+            .iter()
+            .map(|(material, rate)| (self.index_of(material), *rate))
+            .collect();
+        solver::solve(self, &target_indices, user_settings)
+    }
+
+    pub fn material(&self, index: usize) -> &Material {
+        &self.materials[index]
+    }
+
+    pub fn recipe(&self, index: usize) -> &Recipe {
+        &self.recipes[index]
+    }
+
+    pub fn index_of(&self, material: &Material) -> usize {
+        self.materials
+            .iter()
+            .position(|candidate| candidate == material)
+            .unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_the_recipe_making_a_material() {
+        let material_database = MaterialDatabase::built_in().unwrap();
+        let mut user_settings = UserSettings::default();
+        let recipe_for = |name: &str, user_settings: &UserSettings| {
+            let material = material_database.find_material(name).unwrap();
+            material_database
+                .producing_recipe(material, user_settings)
+                .map(|recipe| recipe.name.clone())
+        };
+        assert_eq!(recipe_for("Iron Gear Wheel", &user_settings).as_deref(), Some("Iron Gear Wheel"));
+        assert_eq!(recipe_for("Petroleum Gas", &user_settings).as_deref(), Some("Basic Oil Processing"));
+        assert_eq!(recipe_for("Iron Ore", &user_settings), None);
+
+        let recipe = material_database.find_recipe("advanced oil processing").unwrap();
+        user_settings.choose_recipe(recipe, &material_database);
+        assert_eq!(recipe_for("Petroleum Gas", &user_settings).as_deref(), Some("Advanced Oil Processing"));
+    }
+}
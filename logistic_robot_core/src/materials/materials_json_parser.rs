@@ -0,0 +1,589 @@
+use crate::enums::{ExtractorType, ProducerType};
+use crate::extraction::Extraction;
+use crate::materials::material::Material;
+use crate::recipe::*;
+use fraction::Fraction;
+use core::fmt::Error;
+use core::fmt::Formatter;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt::Display;
+
+#[derive(Serialize, Deserialize)]
+struct JsonFileResult {
+    pub raw: Vec<String>,
+    #[serde(default)]
+    pub raw_costs: HashMap<String, f32>,
+    #[serde(default)]
+    pub fluids: Vec<String>,
+    #[serde(default)]
+    pub extraction: Vec<ExtractionContract>,
+    #[serde(default)]
+    pub aliases: HashMap<String, Vec<String>>,
+    pub non_raw: Vec<NonRawMaterialContract>,
+    #[serde(default)]
+    pub recipes: Vec<RecipeContract>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+struct NonRawMaterialContract {
+    pub name: String,
+    pub producer: String,
+    pub resources: Vec<Resource>,
+    pub amount: f32,
+    pub time: f32,
+    #[serde(default)]
+    pub allow_productivity: bool,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+struct RecipeContract {
+    pub name: String,
+    pub producer: String,
+    pub ingredients: Vec<Resource>,
+    pub products: Vec<Resource>,
+    pub time: f32,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    #[serde(default)]
+    pub allow_productivity: bool,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+struct ExtractionContract {
+    pub name: String,
+    pub extractor: String,
+    #[serde(default = "default_mining_time")]
+    pub mining_time: f32,
+    #[serde(default)]
+    pub requires_fluid: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+struct Resource {
+    pub name: String,
+    pub amount: f32,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum MaterialsParseProblem {
+    InvalidJson(String),
+    DependencyCycle(Vec<String>),
+    UnknownMaterial { location: String, name: String },
+    UnknownProducer { location: String, producer: String },
+    UnknownExtractor { location: String, extractor: String },
+    NotRaw { location: String, name: String },
+    DuplicateName { location: String, name: String },
+    NonPositiveAmount { location: String, name: String, amount: f32 },
+    NegativeTime { location: String, time: f32 },
+}
+impl Display for MaterialsParseProblem {
+    fn fmt(&self, formatter: &mut Formatter) -> Result<(), Error> {
+        match self {
+            MaterialsParseProblem::InvalidJson(why) => write!(formatter, "Invalid json: {}", why),
+            MaterialsParseProblem::DependencyCycle(path) => write!(
+                formatter,
+                "Dependency cycle with no raw input: {}",
+                path.join(" -> ")
+            ),
+            MaterialsParseProblem::UnknownMaterial { location, name } => {
+                write!(formatter, "{}: Unknown material {}", location, name)
+            }
+            MaterialsParseProblem::UnknownProducer { location, producer } => {
+                write!(formatter, "{}: Unknown producer {}", location, producer)
+            }
+            MaterialsParseProblem::UnknownExtractor { location, extractor } => {
+                write!(formatter, "{}: Unknown extractor {}", location, extractor)
+            }
+            MaterialsParseProblem::NotRaw { location, name } => {
+                write!(formatter, "{}: {} is not a raw material", location, name)
+            }
+            MaterialsParseProblem::DuplicateName { location, name } => {
+                write!(formatter, "{}: Duplicate name {}", location, name)
+            }
+            MaterialsParseProblem::NonPositiveAmount {
+                location,
+                name,
+                amount,
+            } => write!(
+                formatter,
+                "{}: Amount of {} must be positive, found {}",
+                location, name, amount
+            ),
+            MaterialsParseProblem::NegativeTime { location, time } => {
+                write!(formatter, "{}: Time must not be negative, found {}", location, time)
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct MaterialsParseError {
+    pub problems: Vec<MaterialsParseProblem>,
+}
+impl Display for MaterialsParseError {
+    fn fmt(&self, formatter: &mut Formatter) -> Result<(), Error> {
+        let problems: Vec<String> = self.problems.iter().map(|problem| problem.to_string()).collect();
+        write!(formatter, "{}", problems.join("\n"))
+    }
+}
+
+pub fn parse_materials_json(
+    json_file_contents: String,
+) -> Result<(Vec<Material>, Vec<Recipe>), MaterialsParseError> {
+    let parsed_json: JsonFileResult = match serde_json::from_str(&json_file_contents) {
+        Ok(parsed_json) => parsed_json,
+        Err(why) => {
+            return Err(MaterialsParseError {
+                problems: vec![MaterialsParseProblem::InvalidJson(why.to_string())],
+            })
+        }
+    };
+    let mut problems: Vec<MaterialsParseProblem> = Vec::new();
+
+    // A material exists for every raw resource, every non raw entry, and every
+    // product of a multi-product recipe.
+    let mut materials: Vec<Material> = Vec::new();
+    let material_names = parsed_json
+        .raw
+        .iter()
+        .enumerate()
+        .map(|(index, name)| (format!("raw[{}]", index), name, true))
+        .chain(
+            parsed_json
+                .non_raw
+                .iter()
+                .enumerate()
+                .map(|(index, contract)| (format!("non_raw[{}]", index), &contract.name, true)),
+        )
+        .chain(parsed_json.recipes.iter().enumerate().flat_map(|(index, contract)| {
+            contract
+                .products
+                .iter()
+                .map(move |product| (format!("recipes[{}]", index), &product.name, false))
+        }));
+    for (location, material_name, must_be_unique) in material_names {
+        if !materials.iter().any(|material| &material.name == material_name) {
+            materials.push(Material::new(material_name));
+        } else if must_be_unique {
+            problems.push(MaterialsParseProblem::DuplicateName {
+                location,
+                name: material_name.clone(),
+            });
+        }
+    }
+    for (name, &cost) in &parsed_json.raw_costs {
+        match materials.iter_mut().find(|material| &material.name == name) {
+            Some(material) => material.raw_cost = Fraction::from(cost),
+            None => problems.push(MaterialsParseProblem::UnknownMaterial {
+                location: "raw_costs".to_string(),
+                name: name.clone(),
+            }),
+        }
+    }
+    for name in &parsed_json.fluids {
+        match materials.iter_mut().find(|material| &material.name == name) {
+            Some(material) => material.fluid = true,
+            None => problems.push(MaterialsParseProblem::UnknownMaterial {
+                location: "fluids".to_string(),
+                name: name.clone(),
+            }),
+        }
+    }
+
+    for (name, aliases) in &parsed_json.aliases {
+        let location = format!("aliases ({})", name);
+        for alias in aliases {
+            let taken = materials.iter().any(|material| {
+                material.name.to_lowercase() == alias.to_lowercase()
+                    || material
+                        .aliases
+                        .iter()
+                        .any(|existing| existing.to_lowercase() == alias.to_lowercase())
+            });
+            if taken {
+                problems.push(MaterialsParseProblem::DuplicateName {
+                    location: location.clone(),
+                    name: alias.clone(),
+                });
+            }
+        }
+        match materials.iter_mut().find(|material| &material.name == name) {
+            Some(material) => material.aliases.extend(aliases.iter().cloned()),
+            None => problems.push(MaterialsParseProblem::UnknownMaterial {
+                location,
+                name: name.clone(),
+            }),
+        }
+    }
+
+    let recipe_contracts: Vec<(String, RecipeContract)> = parsed_json
+        .non_raw
+        .into_iter()
+        .enumerate()
+        .map(|(index, contract)| {
+            (
+                format!("non_raw[{}] ({})", index, contract.name),
+                RecipeContract {
+                    products: vec![Resource {
+                        name: contract.name.clone(),
+                        amount: contract.amount,
+                    }],
+                    name: contract.name,
+                    producer: contract.producer,
+                    ingredients: contract.resources,
+                    time: contract.time,
+                    enabled: true,
+                    allow_productivity: contract.allow_productivity,
+                },
+            )
+        })
+        .chain(
+            parsed_json
+                .recipes
+                .into_iter()
+                .enumerate()
+                .map(|(index, contract)| (format!("recipes[{}] ({})", index, contract.name), contract)),
+        )
+        .collect();
+
+    let mut recipes: Vec<Recipe> = Vec::new();
+    for (location, recipe_contract) in recipe_contracts {
+        if recipes.iter().any(|recipe| recipe.name == recipe_contract.name) {
+            problems.push(MaterialsParseProblem::DuplicateName {
+                location: location.clone(),
+                name: recipe_contract.name.clone(),
+            });
+        }
+        if let Some(recipe) = parse_recipe(&location, recipe_contract, &materials, &mut problems) {
+            for (product_index, _amount) in &recipe.products {
+                materials[*product_index].recipes.push(recipes.len());
+            }
+            recipes.push(recipe);
+        }
+    }
+
+    for (index, extraction_contract) in parsed_json.extraction.iter().enumerate() {
+        let location = format!("extraction[{}] ({})", index, extraction_contract.name);
+        if let Some(extraction) = parse_extraction(&location, extraction_contract, &mut problems) {
+            match materials
+                .iter_mut()
+                .find(|material| material.name == extraction_contract.name)
+            {
+                Some(material) if material.recipes.is_empty() => material.extraction = Some(extraction),
+                Some(_) => problems.push(MaterialsParseProblem::NotRaw {
+                    location,
+                    name: extraction_contract.name.clone(),
+                }),
+                None => problems.push(MaterialsParseProblem::UnknownMaterial {
+                    location,
+                    name: extraction_contract.name.clone(),
+                }),
+            }
+        }
+    }
+
+    problems.extend(
+        find_unproducible_cycles(&materials, &recipes)
+            .into_iter()
+            .map(MaterialsParseProblem::DependencyCycle),
+    );
+    if problems.is_empty() {
+        Ok((materials, recipes))
+    } else {
+        Err(MaterialsParseError { problems })
+    }
+}
+
+fn parse_recipe(
+    location: &str,
+    recipe_contract: RecipeContract,
+    materials: &[Material],
+    problems: &mut Vec<MaterialsParseProblem>,
+) -> Option<Recipe> {
+    let problem_count = problems.len();
+    let producer_type = get_producer_type(&recipe_contract.producer);
+    if producer_type.is_none() {
+        problems.push(MaterialsParseProblem::UnknownProducer {
+            location: location.to_string(),
+            producer: recipe_contract.producer.clone(),
+        });
+    }
+    if recipe_contract.time < 0.0 {
+        problems.push(MaterialsParseProblem::NegativeTime {
+            location: location.to_string(),
+            time: recipe_contract.time,
+        });
+    }
+    let ingredients = parse_resources(location, &recipe_contract.ingredients, materials, problems);
+    let products = parse_resources(location, &recipe_contract.products, materials, problems);
+    match producer_type {
+        Some(producer_type) if problems.len() == problem_count => Some(Recipe::new(
+            &recipe_contract.name,
+            producer_type,
+            ingredients,
+            products,
+            Fraction::from(recipe_contract.time),
+            recipe_contract.enabled,
+            recipe_contract.allow_productivity,
+        )),
+        _ => None,
+    }
+}
+
+fn parse_extraction(
+    location: &str,
+    extraction_contract: &ExtractionContract,
+    problems: &mut Vec<MaterialsParseProblem>,
+) -> Option<Extraction> {
+    if extraction_contract.mining_time <= 0.0 {
+        problems.push(MaterialsParseProblem::NonPositiveAmount {
+            location: location.to_string(),
+            name: "mining time".to_string(),
+            amount: extraction_contract.mining_time,
+        });
+        return None;
+    }
+    let extractor_type = match &*extraction_contract.extractor {
+        "Mining Drill" => ExtractorType::MiningDrill,
+        "Pumpjack" => ExtractorType::Pumpjack,
+        "Offshore Pump" => ExtractorType::OffshorePump,
+        _ => {
+            problems.push(MaterialsParseProblem::UnknownExtractor {
+                location: location.to_string(),
+                extractor: extraction_contract.extractor.clone(),
+            });
+            return None;
+        }
+    };
+    Some(Extraction::new(
+        extractor_type,
+        Fraction::from(extraction_contract.mining_time),
+        extraction_contract.requires_fluid,
+    ))
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+fn default_mining_time() -> f32 {
+    1.0
+}
+
+fn parse_resources(
+    location: &str,
+    resources: &[Resource],
+    materials: &[Material],
+    problems: &mut Vec<MaterialsParseProblem>,
+) -> Vec<(usize, Fraction)> {
+    let mut parsed_resources: Vec<(usize, Fraction)> = Vec::new();
+    for resource in resources {
+        if resource.amount <= 0.0 {
+            problems.push(MaterialsParseProblem::NonPositiveAmount {
+                location: location.to_string(),
+                name: resource.name.clone(),
+                amount: resource.amount,
+            });
+        }
+        match materials
+            .iter()
+            .position(|material| material.name == resource.name)
+        {
+            Some(index) => parsed_resources.push((index, Fraction::from(resource.amount))),
+            None => problems.push(MaterialsParseProblem::UnknownMaterial {
+                location: location.to_string(),
+                name: resource.name.clone(),
+            }),
+        }
+    }
+    parsed_resources
+}
+
+// A cycle is only a problem when nothing in it can ever be made from raw
+// materials; loops with a way in (e.g. Kovarex enrichment) are left to the
+// solver. Each reported path starts and ends on the same material.
+fn find_unproducible_cycles(materials: &[Material], recipes: &[Recipe]) -> Vec<Vec<String>> {
+    let mut producible: Vec<bool> = materials.iter().map(|material| material.recipes.is_empty()).collect();
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for recipe in recipes {
+            if recipe.ingredients.iter().all(|(index, _amount)| producible[*index]) {
+                for (index, _amount) in &recipe.products {
+                    if !producible[*index] {
+                        producible[*index] = true;
+                        changed = true;
+                    }
+                }
+            }
+        }
+    }
+
+    let mut cycles: Vec<Vec<String>> = Vec::new();
+    let mut reported: Vec<bool> = vec![false; materials.len()];
+    for start in 0..materials.len() {
+        if producible[start] || reported[start] {
+            continue;
+        }
+        // Every recipe of an unproducible material has an unproducible
+        // ingredient, so following them must eventually revisit a material.
+        let mut path: Vec<usize> = vec![start];
+        let mut current = start;
+        loop {
+            let possible_next = materials[current]
+                .recipes
+                .iter()
+                .flat_map(|&recipe_index| recipes[recipe_index].ingredients.iter())
+                .map(|(index, _amount)| *index)
+                .find(|&index| !producible[index]);
+            let next = match possible_next {
+                Some(next) => next,
+                None => {
+                    for &index in &path {
+                        reported[index] = true;
+                    }
+                    break;
+                }
+            };
+            if reported[next] {
+                for &index in &path {
+                    reported[index] = true;
+                }
+                break;
+            }
+            if let Some(cycle_start) = path.iter().position(|&index| index == next) {
+                let cycle = &path[cycle_start..];
+                if cycle.iter().all(|&index| !reported[index]) {
+                    let mut names: Vec<String> =
+                        cycle.iter().map(|&index| materials[index].name.clone()).collect();
+                    names.push(materials[next].name.clone());
+                    cycles.push(names);
+                }
+                for &index in &path {
+                    reported[index] = true;
+                }
+                break;
+            }
+            path.push(next);
+            current = next;
+        }
+    }
+    cycles
+}
+
+fn get_producer_type(s: &str) -> Option<ProducerType> {
+    match s {
+        "Assembling Machine" => Some(ProducerType::AssemblingMachine),
+        "Furnace" => Some(ProducerType::Furnace),
+        "Chemical Plant" => Some(ProducerType::ChemicalPlant),
+        "Rocket Silo" => Some(ProducerType::RocketSilo),
+        "Oil Refinery" => Some(ProducerType::OilRefinery),
+        "Centrifuge" => Some(ProducerType::Centrifuge),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn problems(json: &str) -> Vec<MaterialsParseProblem> {
+        parse_materials_json(json.to_string()).unwrap_err().problems
+    }
+
+    fn cycle(names: &[&str]) -> MaterialsParseProblem {
+        MaterialsParseProblem::DependencyCycle(names.iter().map(|name| name.to_string()).collect())
+    }
+
+    #[test]
+    fn reports_a_material_made_from_itself() {
+        let json = r#"{
+            "raw": ["Ore"],
+            "non_raw": [
+                {"name": "Seed", "producer": "Assembling Machine", "resources": [{"name": "Seed", "amount": 1}], "amount": 2, "time": 1}
+            ]
+        }"#;
+        assert_eq!(problems(json), vec![cycle(&["Seed", "Seed"])]);
+    }
+
+    #[test]
+    fn reports_the_full_path_of_a_cycle() {
+        let json = r#"{
+            "raw": ["Ore"],
+            "non_raw": [
+                {"name": "Egg", "producer": "Assembling Machine", "resources": [{"name": "Chicken", "amount": 1}], "amount": 1, "time": 1},
+                {"name": "Chicken", "producer": "Assembling Machine", "resources": [{"name": "Egg", "amount": 1}], "amount": 1, "time": 1}
+            ]
+        }"#;
+        assert_eq!(problems(json), vec![cycle(&["Egg", "Chicken", "Egg"])]);
+    }
+
+    #[test]
+    fn accepts_cycles_with_a_way_in() {
+        let json = r#"{
+            "raw": ["Ore"],
+            "non_raw": [
+                {"name": "Seed", "producer": "Assembling Machine", "resources": [{"name": "Ore", "amount": 1}], "amount": 1, "time": 1}
+            ],
+            "recipes": [
+                {"name": "Seed Growing", "producer": "Assembling Machine", "ingredients": [{"name": "Seed", "amount": 1}],
+                 "products": [{"name": "Seed", "amount": 2}], "time": 10}
+            ]
+        }"#;
+        let (materials, recipes) = parse_materials_json(json.to_string()).unwrap();
+        assert_eq!(materials.len(), 2);
+        assert_eq!(recipes.len(), 2);
+    }
+
+    #[test]
+    fn collects_every_problem_in_one_pass() {
+        let json = r#"{
+            "raw": ["Ore"],
+            "non_raw": [
+                {"name": "Plate", "producer": "Furnace", "resources": [{"name": "Ore", "amount": 1}], "amount": 1, "time": 3.2},
+                {"name": "Plate", "producer": "Furnace", "resources": [{"name": "Ore", "amount": 1}], "amount": 1, "time": 1},
+                {"name": "Gear", "producer": "Smelter", "resources": [{"name": "Plate", "amount": 0}], "amount": 1, "time": -1},
+                {"name": "Wire", "producer": "Assembling Machine", "resources": [{"name": "Copper", "amount": 1}], "amount": 2, "time": 0.5}
+            ]
+        }"#;
+        assert_eq!(
+            problems(json),
+            vec![
+                MaterialsParseProblem::DuplicateName {
+                    location: "non_raw[1]".to_string(),
+                    name: "Plate".to_string(),
+                },
+                MaterialsParseProblem::DuplicateName {
+                    location: "non_raw[1] (Plate)".to_string(),
+                    name: "Plate".to_string(),
+                },
+                MaterialsParseProblem::UnknownProducer {
+                    location: "non_raw[2] (Gear)".to_string(),
+                    producer: "Smelter".to_string(),
+                },
+                MaterialsParseProblem::NegativeTime {
+                    location: "non_raw[2] (Gear)".to_string(),
+                    time: -1.0,
+                },
+                MaterialsParseProblem::NonPositiveAmount {
+                    location: "non_raw[2] (Gear)".to_string(),
+                    name: "Plate".to_string(),
+                    amount: 0.0,
+                },
+                MaterialsParseProblem::UnknownMaterial {
+                    location: "non_raw[3] (Wire)".to_string(),
+                    name: "Copper".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_invalid_json() {
+        assert!(matches!(
+            problems("{\"raw\": [")[..],
+            [MaterialsParseProblem::InvalidJson(_)]
+        ));
+    }
+}
@@ -0,0 +1,4 @@
+pub mod material_database;
+pub mod material;
+pub mod materials_json_parser;
+pub mod name_lookup;
\ No newline at end of file
@@ -0,0 +1,284 @@
+use core::fmt::Error;
+use core::fmt::Formatter;
+use std::fmt::Display;
+
+// A match at most this far from the query is used without asking, as long as
+// no other name is just as close. Short queries must match more closely.
+const MAX_AUTOMATIC_SCORE: usize = 2;
+const MAX_SUGGESTIONS: usize = 5;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NameLookupError {
+    pub kind: &'static str,
+    pub name: String,
+    pub suggestions: Vec<String>,
+}
+impl Display for NameLookupError {
+    fn fmt(&self, formatter: &mut Formatter) -> Result<(), Error> {
+        write!(formatter, "Unable to find {} with name *{}*", self.kind, self.name)?;
+        match self.suggestions.as_slice() {
+            [] => Ok(()),
+            [suggestion] => write!(formatter, ". Did you mean *{}*?", suggestion),
+            [suggestions @ .., last] => write!(
+                formatter,
+                ". Did you mean {} or *{}*?",
+                suggestions
+                    .iter()
+                    .map(|suggestion| format!("*{}*", suggestion))
+                    .collect::<Vec<String>>()
+                    .join(", "),
+                last
+            ),
+        }
+    }
+}
+
+// Finds the candidate whose name best matches the query. Each candidate is the
+// name to match against, the name to suggest (they differ for aliases) and the
+// value itself. Exact matches (ignoring case) always win; otherwise names are
+// compared after normalising plurals, both as a whole and word by word, so
+// "logistics science pack" and "circuit" still find something.
+pub fn lookup_by_name<'a, T>(
+    kind: &'static str,
+    query: &str,
+    candidates: impl Iterator<Item = (&'a str, &'a str, T)>,
+) -> Result<T, NameLookupError> {
+    let query = query.trim();
+    let query_words = normalize(query);
+    let mut scored: Vec<(usize, &'a str, T)> = Vec::new();
+    for (name, suggested_name, candidate) in candidates {
+        if name.to_lowercase() == query.to_lowercase() {
+            return Ok(candidate);
+        }
+        if let Some(score) = suggestion_score(&query_words, &normalize(name)) {
+            scored.push((score, suggested_name, candidate));
+        }
+    }
+    sort_and_deduplicate(&mut scored);
+    let maximum_automatic_score = maximum_automatic_score(&query_words);
+    let unambiguous = match scored.as_slice() {
+        [(best, _, _)] => *best <= maximum_automatic_score,
+        [(best, _, _), (second, _, _), ..] => *best <= maximum_automatic_score && best < second,
+        [] => false,
+    };
+    if unambiguous {
+        return Ok(scored.remove(0).2);
+    }
+    Err(NameLookupError {
+        kind,
+        name: query.to_string(),
+        suggestions: scored
+            .into_iter()
+            .take(MAX_SUGGESTIONS)
+            .map(|(_score, name, _candidate)| name.to_string())
+            .collect(),
+    })
+}
+
+// Every candidate close enough to be suggested, closest first, for listing
+// matches rather than picking one. Exact matches come first.
+pub fn search_by_name<'a, T>(
+    query: &str,
+    candidates: impl Iterator<Item = (&'a str, &'a str, T)>,
+    limit: usize,
+) -> Vec<T> {
+    let query = query.trim();
+    let query_words = normalize(query);
+    let mut scored: Vec<(usize, &'a str, T)> = Vec::new();
+    for (name, suggested_name, candidate) in candidates {
+        let score = match name.to_lowercase() == query.to_lowercase() {
+            true => Some(0),
+            false => suggestion_score(&query_words, &normalize(name)).map(|score| score + 1),
+        };
+        if let Some(score) = score {
+            scored.push((score, suggested_name, candidate));
+        }
+    }
+    sort_and_deduplicate(&mut scored);
+    scored
+        .into_iter()
+        .take(limit)
+        .map(|(_score, _name, candidate)| candidate)
+        .collect()
+}
+
+// Only the closest of an item's names counts, so an item matching both by name
+// and by alias isn't ambiguous with itself or listed twice.
+fn sort_and_deduplicate<T>(scored: &mut Vec<(usize, &str, T)>) {
+    scored.sort_by(|(score_a, name_a, _), (score_b, name_b, _)| {
+        score_a.cmp(score_b).then(name_a.cmp(name_b))
+    });
+    let mut seen: Vec<&str> = Vec::new();
+    scored.retain(|(_score, name, _candidate)| {
+        let first = !seen.contains(name);
+        seen.push(name);
+        first
+    });
+}
+
+fn maximum_automatic_score(query_words: &[String]) -> usize {
+    let length: usize = query_words.iter().map(|word| word.len()).sum();
+    (length / 4).min(MAX_AUTOMATIC_SCORE)
+}
+
+fn maximum_suggestion_score(query_words: &[String]) -> usize {
+    let length: usize = query_words.iter().map(|word| word.len()).sum();
+    (length / 3).max(1)
+}
+
+// Names holding every query word are always worth suggesting. Others have to
+// be within a few typos, fewer for short queries, so "gear" suggests Iron Gear
+// Wheel but not Car or Lab.
+fn suggestion_score(query_words: &[String], name_words: &[String]) -> Option<usize> {
+    let score = score(query_words, name_words);
+    match partial_score(query_words, name_words) {
+        Some(_partial) => Some(score),
+        None if score <= maximum_suggestion_score(query_words) => Some(score),
+        None => None,
+    }
+}
+
+// Lowercase words with simple English plurals removed.
+fn normalize(name: &str) -> Vec<String> {
+    name.to_lowercase()
+        .split(|c: char| c.is_whitespace() || c == '-')
+        .filter(|word| !word.is_empty())
+        .map(|word| {
+            if let Some(stem) = word.strip_suffix("ies") {
+                format!("{}y", stem)
+            } else if word.len() > 3 && word.ends_with('s') && !word.ends_with("ss") {
+                word[..word.len() - 1].to_string()
+            } else {
+                word.to_string()
+            }
+        })
+        .collect()
+}
+
+// Lower is better. Whole-name edit distance catches typos, sorted words catch
+// reordering, and a word-by-word match catches names typed only in part, at a
+// cost of one per word left out.
+fn score(query_words: &[String], name_words: &[String]) -> usize {
+    let whole = edit_distance(&query_words.join(" "), &name_words.join(" "));
+    let mut sorted_query = query_words.to_vec();
+    sorted_query.sort();
+    let mut sorted_name = name_words.to_vec();
+    sorted_name.sort();
+    let reordered = edit_distance(&sorted_query.join(" "), &sorted_name.join(" "));
+    let partial = partial_score(query_words, name_words).unwrap_or(usize::MAX);
+    whole.min(reordered).min(partial)
+}
+
+fn partial_score(query_words: &[String], name_words: &[String]) -> Option<usize> {
+    if query_words.is_empty() || query_words.len() > name_words.len() {
+        return None;
+    }
+    let mut unused: Vec<&String> = name_words.iter().collect();
+    let mut distance = 0;
+    for query_word in query_words {
+        let (position, word_distance) = unused
+            .iter()
+            .enumerate()
+            .map(|(position, name_word)| (position, edit_distance(query_word, name_word)))
+            .min_by_key(|(_position, word_distance)| *word_distance)?;
+        if word_distance > query_word.len() / 4 {
+            return None;
+        }
+        distance += word_distance;
+        unused.remove(position);
+    }
+    Some(distance + unused.len())
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b_chars: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b_chars.len()).collect();
+    for (i, a_char) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, b_char) in b_chars.iter().enumerate() {
+            let substitution = previous[j] + if a_char == *b_char { 0 } else { 1 };
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b_chars.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NAMES: [&str; 6] = [
+        "Iron Gear Wheel",
+        "Iron Plate",
+        "Electronic Circuit",
+        "Advanced Circuit",
+        "Logistic Science Pack",
+        "Lab",
+    ];
+
+    fn lookup(query: &str) -> Result<&'static str, NameLookupError> {
+        lookup_by_name("item", query, NAMES.iter().map(|name| (*name, *name, *name)))
+    }
+
+    #[test]
+    fn scores_typos_reordering_and_partial_names() {
+        let words = |name: &str| normalize(name);
+        assert_eq!(score(&words("iron plate"), &words("Iron Plate")), 0);
+        assert_eq!(score(&words("iron plates"), &words("Iron Plate")), 0);
+        assert_eq!(score(&words("iorn plate"), &words("Iron Plate")), 2);
+        assert_eq!(score(&words("plate iron"), &words("Iron Plate")), 0);
+        assert_eq!(score(&words("logistics science"), &words("Logistic Science Pack")), 1);
+        assert_eq!(score(&words("gear"), &words("Iron Gear Wheel")), 2);
+    }
+
+    #[test]
+    fn picks_close_unambiguous_matches() {
+        assert_eq!(lookup("IRON PLATE"), Ok("Iron Plate"));
+        assert_eq!(lookup("iron plat"), Ok("Iron Plate"));
+        assert_eq!(lookup("logistics science packs"), Ok("Logistic Science Pack"));
+    }
+
+    #[test]
+    fn suggests_closest_names_first() {
+        let error = lookup("circuit").unwrap_err();
+        assert_eq!(error.suggestions, vec!["Advanced Circuit", "Electronic Circuit"]);
+        assert_eq!(
+            error.to_string(),
+            "Unable to find item with name *circuit*. Did you mean *Advanced Circuit* or *Electronic Circuit*?"
+        );
+        assert_eq!(lookup("zzzzzzzzzzzz").unwrap_err().suggestions, Vec::<String>::new());
+    }
+
+    #[test]
+    fn counts_each_item_once_across_its_aliases() {
+        let candidates = vec![
+            ("Electronic Circuit", "Electronic Circuit", 1),
+            ("green circuit", "Electronic Circuit", 1),
+            ("Advanced Circuit", "Advanced Circuit", 2),
+            ("red circuit", "Advanced Circuit", 2),
+        ];
+        assert_eq!(lookup_by_name("item", "green circuits", candidates.clone().into_iter()), Ok(1));
+        let error = lookup_by_name("item", "circuit", candidates.into_iter()).unwrap_err();
+        assert_eq!(error.suggestions, vec!["Advanced Circuit", "Electronic Circuit"]);
+    }
+
+    #[test]
+    fn keeps_short_queries_to_related_names() {
+        let names = ["Car", "Coal", "Gate", "Lab", "Iron Gear Wheel", "Iron Plate"];
+        let candidates = || names.iter().map(|name| (*name, *name, *name));
+        let error = lookup_by_name("item", "gear", candidates()).unwrap_err();
+        assert_eq!(error.suggestions, vec!["Iron Gear Wheel"]);
+        assert_eq!(search_by_name("gears", candidates(), 10), vec!["Iron Gear Wheel"]);
+        assert_eq!(lookup_by_name("item", "cal", candidates()).unwrap_err().suggestions, vec!["Car", "Coal"]);
+        assert_eq!(lookup_by_name("item", "iorn plate", candidates()), Ok("Iron Plate"));
+    }
+
+    #[test]
+    fn searches_with_exact_matches_first() {
+        let found = search_by_name("lab", NAMES.iter().map(|name| (*name, *name, *name)), 10);
+        assert_eq!(found.first(), Some(&"Lab"));
+        let limited = search_by_name("circuit", NAMES.iter().map(|name| (*name, *name, *name)), 1);
+        assert_eq!(limited, vec!["Advanced Circuit"]);
+    }
+}
@@ -0,0 +1,216 @@
+use crate::enums::{AssemblingMachineLevel, FurnaceLevel, Module, ProducerType};
+use core::fmt::Error;
+use core::fmt::Formatter;
+use fraction::Fraction;
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+
+// Beacons are assumed to hold two speed module 3s at 50% distribution
+// efficiency, which works out to +50% speed per beacon.
+const BEACON_SPEED_BONUS: (u64, u64) = (1, 2);
+// The same two speed module 3s add +70% energy consumption per beacon.
+const BEACON_CONSUMPTION_BONUS: (u64, u64) = (7, 10);
+// More beacons than can surround a machine are a mistake, not a build.
+pub const MAX_BEACONS: u32 = 12;
+// Machines can never be slowed below 20% of their base speed.
+const MINIMUM_SPEED_MULTIPLIER: (u64, u64) = (1, 5);
+// Nor can their energy consumption drop below 20%.
+const MINIMUM_CONSUMPTION_MULTIPLIER: (u64, u64) = (1, 5);
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ModuleConfiguration {
+    pub modules: Vec<Module>,
+    pub beacons: u32,
+}
+impl ModuleConfiguration {
+    pub fn new(modules: Vec<Module>, beacons: u32) -> Self {
+        ModuleConfiguration { modules, beacons }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.modules.is_empty() && self.beacons == 0
+    }
+
+    pub fn limited_to(&self, slots: usize, allow_productivity: bool) -> ModuleConfiguration {
+        self.fitted_to(slots, allow_productivity).0
+    }
+
+    // Drops modules that don't fit in the machine, and productivity modules
+    // when the recipe doesn't accept them, returning the dropped modules too
+    // so they can be reported.
+    pub fn fitted_to(&self, slots: usize, allow_productivity: bool) -> (ModuleConfiguration, Vec<Module>) {
+        let mut modules: Vec<Module> = Vec::new();
+        let mut left_out: Vec<Module> = Vec::new();
+        for module in &self.modules {
+            if modules.len() < slots && (allow_productivity || module.productivity() == Fraction::from(0)) {
+                modules.push(*module);
+            } else {
+                left_out.push(*module);
+            }
+        }
+        (
+            ModuleConfiguration {
+                modules,
+                beacons: self.beacons,
+            },
+            left_out,
+        )
+    }
+
+    pub fn speed_multiplier(&self) -> Fraction {
+        let module_bonus = self
+            .modules
+            .iter()
+            .fold(Fraction::from(0), |total, module| total + module.speed());
+        let beacon_bonus =
+            Fraction::new(BEACON_SPEED_BONUS.0, BEACON_SPEED_BONUS.1) * Fraction::from(self.beacons);
+        let multiplier = Fraction::from(1) + module_bonus + beacon_bonus;
+        let minimum = Fraction::new(MINIMUM_SPEED_MULTIPLIER.0, MINIMUM_SPEED_MULTIPLIER.1);
+        if multiplier < minimum {
+            minimum
+        } else {
+            multiplier
+        }
+    }
+
+    pub fn consumption_multiplier(&self) -> Fraction {
+        let module_bonus = self
+            .modules
+            .iter()
+            .fold(Fraction::from(0), |total, module| total + module.consumption());
+        let beacon_bonus = Fraction::new(BEACON_CONSUMPTION_BONUS.0, BEACON_CONSUMPTION_BONUS.1)
+            * Fraction::from(self.beacons);
+        let multiplier = Fraction::from(1) + module_bonus + beacon_bonus;
+        let minimum = Fraction::new(
+            MINIMUM_CONSUMPTION_MULTIPLIER.0,
+            MINIMUM_CONSUMPTION_MULTIPLIER.1,
+        );
+        if multiplier < minimum {
+            minimum
+        } else {
+            multiplier
+        }
+    }
+
+    // Pollution scales with energy consumption, on top of the modules' own
+    // pollution bonus.
+    pub fn pollution_multiplier(&self) -> Fraction {
+        let module_bonus = self
+            .modules
+            .iter()
+            .fold(Fraction::from(0), |total, module| total + module.pollution());
+        self.consumption_multiplier() * (Fraction::from(1) + module_bonus)
+    }
+
+    pub fn productivity_multiplier(&self) -> Fraction {
+        self.modules
+            .iter()
+            .fold(Fraction::from(1), |total, module| total + module.productivity())
+    }
+}
+impl Display for ModuleConfiguration {
+    fn fmt(&self, formatter: &mut Formatter) -> Result<(), Error> {
+        let modules = match self.modules.is_empty() {
+            true => "No modules".to_string(),
+            false => self
+                .modules
+                .iter()
+                .map(|module| module.to_string())
+                .collect::<Vec<String>>()
+                .join(", "),
+        };
+        match self.beacons {
+            0 => write!(formatter, "{}", modules),
+            1 => write!(formatter, "{} + 1 beacon", modules),
+            beacons => write!(formatter, "{} + {} beacons", modules, beacons),
+        }
+    }
+}
+
+impl Module {
+    pub fn speed(&self) -> Fraction {
+        match self {
+            Module::Speed1 => Fraction::new(2u64, 10u64),
+            Module::Speed2 => Fraction::new(3u64, 10u64),
+            Module::Speed3 => Fraction::new(5u64, 10u64),
+            Module::Productivity1 => -Fraction::new(5u64, 100u64),
+            Module::Productivity2 => -Fraction::new(10u64, 100u64),
+            Module::Productivity3 => -Fraction::new(15u64, 100u64),
+            _ => Fraction::from(0),
+        }
+    }
+
+    pub fn productivity(&self) -> Fraction {
+        match self {
+            Module::Productivity1 => Fraction::new(4u64, 100u64),
+            Module::Productivity2 => Fraction::new(6u64, 100u64),
+            Module::Productivity3 => Fraction::new(10u64, 100u64),
+            _ => Fraction::from(0),
+        }
+    }
+
+    pub fn pollution(&self) -> Fraction {
+        match self {
+            Module::Productivity1 => Fraction::new(5u64, 100u64),
+            Module::Productivity2 => Fraction::new(7u64, 100u64),
+            Module::Productivity3 => Fraction::new(10u64, 100u64),
+            _ => Fraction::from(0),
+        }
+    }
+
+    pub fn consumption(&self) -> Fraction {
+        match self {
+            Module::Speed1 => Fraction::new(50u64, 100u64),
+            Module::Speed2 => Fraction::new(60u64, 100u64),
+            Module::Speed3 => Fraction::new(70u64, 100u64),
+            Module::Productivity1 => Fraction::new(40u64, 100u64),
+            Module::Productivity2 => Fraction::new(60u64, 100u64),
+            Module::Productivity3 => Fraction::new(80u64, 100u64),
+            Module::Efficiency1 => -Fraction::new(30u64, 100u64),
+            Module::Efficiency2 => -Fraction::new(40u64, 100u64),
+            Module::Efficiency3 => -Fraction::new(50u64, 100u64),
+        }
+    }
+}
+
+pub fn module_slots(
+    producer_type: &ProducerType,
+    assembling_machine_level: &AssemblingMachineLevel,
+    furnace_level: &FurnaceLevel,
+) -> usize {
+    match producer_type {
+        ProducerType::AssemblingMachine => match assembling_machine_level {
+            AssemblingMachineLevel::One => 0,
+            AssemblingMachineLevel::Two => 2,
+            AssemblingMachineLevel::Three => 4,
+        },
+        ProducerType::Furnace => match furnace_level {
+            FurnaceLevel::Stone | FurnaceLevel::Steel => 0,
+            FurnaceLevel::Electric => 2,
+        },
+        ProducerType::ChemicalPlant => 3,
+        ProducerType::OilRefinery => 3,
+        ProducerType::Centrifuge => 2,
+        ProducerType::RocketSilo => 4,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reports_modules_left_out() {
+        let module_configuration = ModuleConfiguration::new(
+            vec![Module::Productivity3, Module::Speed3, Module::Speed3, Module::Efficiency1],
+            1,
+        );
+        let (fitted, left_out) = module_configuration.fitted_to(2, false);
+        assert_eq!(fitted, ModuleConfiguration::new(vec![Module::Speed3, Module::Speed3], 1));
+        assert_eq!(left_out, vec![Module::Productivity3, Module::Efficiency1]);
+
+        let (fitted, left_out) = module_configuration.fitted_to(4, true);
+        assert_eq!(fitted, module_configuration);
+        assert!(left_out.is_empty());
+    }
+}
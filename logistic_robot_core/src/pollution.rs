@@ -0,0 +1,140 @@
+use crate::enums::{AssemblingMachineLevel, Extractor, FurnaceLevel, ProducerType};
+use crate::recipe::Recipe;
+use crate::user_settings::UserSettings;
+use fraction::{CheckedAdd, CheckedMul, Fraction};
+use std::collections::HashMap;
+
+// Pollution emitted per minute by a single working machine.
+pub fn producer_pollution(
+    producer_type: &ProducerType,
+    assembling_machine_level: &AssemblingMachineLevel,
+    furnace_level: &FurnaceLevel,
+) -> Fraction {
+    Fraction::from(match producer_type {
+        ProducerType::AssemblingMachine => match assembling_machine_level {
+            AssemblingMachineLevel::One => 4,
+            AssemblingMachineLevel::Two => 3,
+            AssemblingMachineLevel::Three => 2,
+        },
+        ProducerType::Furnace => match furnace_level {
+            FurnaceLevel::Stone => 2,
+            FurnaceLevel::Steel => 4,
+            FurnaceLevel::Electric => 1,
+        },
+        ProducerType::ChemicalPlant => 4,
+        ProducerType::OilRefinery => 6,
+        ProducerType::Centrifuge => 4,
+        ProducerType::RocketSilo => 0,
+    })
+}
+
+pub fn extractor_pollution(extractor: &Extractor) -> Fraction {
+    Fraction::from(match extractor {
+        Extractor::BurnerMiningDrill => 12,
+        Extractor::ElectricMiningDrill => 10,
+        Extractor::Pumpjack => 10,
+        Extractor::OffshorePump => 0,
+    })
+}
+
+// Pollution per minute, split by machine. Idle machines don't pollute, so
+// only the busy machine count matters.
+#[derive(Clone, Debug)]
+pub struct PollutionEstimate {
+    pub producers: HashMap<ProducerType, Fraction>,
+    pub extractors: HashMap<Extractor, Fraction>,
+}
+impl PollutionEstimate {
+    pub fn total(&self) -> Fraction {
+        self.producers
+            .values()
+            .chain(self.extractors.values())
+            .fold(Fraction::from(0), |total, pollution| total + *pollution)
+    }
+}
+
+// None when the totals get too large to hold.
+pub fn estimate_pollution(
+    user_settings: &UserSettings,
+    producers: &[(&Recipe, Fraction)],
+    extractors: &[(Extractor, Fraction)],
+) -> Option<PollutionEstimate> {
+    let mut estimate = PollutionEstimate {
+        producers: HashMap::new(),
+        extractors: HashMap::new(),
+    };
+    for (recipe, amount) in producers {
+        let pollution = producer_pollution(
+            &recipe.producer_type,
+            &user_settings.assembling_machine_level_for(recipe),
+            &user_settings.furnace_level_for(recipe),
+        )
+        .checked_mul(&user_settings.module_configuration(recipe).pollution_multiplier())?
+        .checked_mul(amount)?;
+        let total = estimate
+            .producers
+            .entry(recipe.producer_type)
+            .or_insert_with(|| Fraction::from(0));
+        *total = total.checked_add(&pollution)?;
+    }
+    for (extractor, amount) in extractors {
+        let pollution = extractor_pollution(extractor).checked_mul(amount)?;
+        let total = estimate
+            .extractors
+            .entry(*extractor)
+            .or_insert_with(|| Fraction::from(0));
+        *total = total.checked_add(&pollution)?;
+    }
+    // Summed once here, so total() can't overflow later.
+    estimate
+        .producers
+        .values()
+        .chain(estimate.extractors.values())
+        .try_fold(Fraction::from(0), |total, pollution| total.checked_add(pollution))?;
+    Some(estimate)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::enums::MiningDrillLevel;
+    use crate::materials::material_database::MaterialDatabase;
+    use crate::Calculator;
+
+    fn gear_pollution(user_settings: UserSettings) -> PollutionEstimate {
+        let material_database = MaterialDatabase::built_in().unwrap();
+        Calculator::new(&material_database)
+            .settings(user_settings)
+            .items("iron gear wheel")
+            .calculate()
+            .unwrap()
+            .pollution
+    }
+
+    // One gear a second takes one assembling machine 1, 6.4 stone furnaces
+    // and four electric mining drills for the 2 ore a second.
+    #[test]
+    fn estimates_a_gear_chain() {
+        let pollution = gear_pollution(UserSettings::default());
+        assert_eq!(pollution.producers[&ProducerType::AssemblingMachine], Fraction::from(4));
+        assert_eq!(pollution.producers[&ProducerType::Furnace], Fraction::new(64u64, 5u64));
+        assert_eq!(pollution.extractors[&Extractor::ElectricMiningDrill], Fraction::from(40));
+        assert_eq!(pollution.total(), Fraction::new(284u64, 5u64));
+    }
+
+    #[test]
+    fn follows_machine_levels() {
+        let pollution = gear_pollution(UserSettings {
+            assembling_machine_level: AssemblingMachineLevel::Two,
+            furnace_level: FurnaceLevel::Electric,
+            mining_drill_level: MiningDrillLevel::Burner,
+            ..UserSettings::default()
+        });
+        // Two thirds of an assembling machine 2, 3.2 electric furnaces and
+        // eight burner mining drills.
+        assert_eq!(pollution.producers[&ProducerType::AssemblingMachine], Fraction::from(2));
+        assert_eq!(pollution.producers[&ProducerType::Furnace], Fraction::new(16u64, 5u64));
+        assert_eq!(pollution.extractors[&Extractor::BurnerMiningDrill], Fraction::from(96));
+        assert_eq!(pollution.total(), Fraction::new(506u64, 5u64));
+    }
+}
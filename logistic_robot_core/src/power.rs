@@ -0,0 +1,217 @@
+use crate::enums::{AssemblingMachineLevel, Extractor, FurnaceLevel, ProducerType};
+use crate::recipe::Recipe;
+use crate::user_settings::UserSettings;
+use fraction::{CheckedAdd, CheckedDiv, CheckedMul, Fraction};
+use std::collections::HashMap;
+
+// Energy held by one piece of coal, in kJ.
+const COAL_FUEL_VALUE: u64 = 4000;
+
+// Energy draw of a single machine in kW. Electric machines keep drawing their
+// drain even while idle, burner machines only burn fuel while working.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MachinePower {
+    Electric { usage: Fraction, drain: Fraction },
+    Burner { usage: Fraction },
+    None,
+}
+
+pub fn producer_power(
+    producer_type: &ProducerType,
+    assembling_machine_level: &AssemblingMachineLevel,
+    furnace_level: &FurnaceLevel,
+) -> MachinePower {
+    match producer_type {
+        ProducerType::AssemblingMachine => match assembling_machine_level {
+            AssemblingMachineLevel::One => electric(75, (5, 2)),
+            AssemblingMachineLevel::Two => electric(150, (5, 1)),
+            AssemblingMachineLevel::Three => electric(375, (25, 2)),
+        },
+        ProducerType::Furnace => match furnace_level {
+            FurnaceLevel::Stone | FurnaceLevel::Steel => MachinePower::Burner {
+                usage: Fraction::from(90),
+            },
+            FurnaceLevel::Electric => electric(180, (6, 1)),
+        },
+        ProducerType::ChemicalPlant => electric(210, (7, 1)),
+        ProducerType::OilRefinery => electric(420, (14, 1)),
+        ProducerType::Centrifuge => electric(350, (58, 5)),
+        ProducerType::RocketSilo => electric(4000, (0, 1)),
+    }
+}
+
+pub fn extractor_power(extractor: &Extractor) -> MachinePower {
+    match extractor {
+        Extractor::BurnerMiningDrill => MachinePower::Burner {
+            usage: Fraction::from(150),
+        },
+        Extractor::ElectricMiningDrill => electric(90, (0, 1)),
+        Extractor::Pumpjack => electric(90, (0, 1)),
+        Extractor::OffshorePump => MachinePower::None,
+    }
+}
+
+fn electric(usage: u64, drain: (u64, u64)) -> MachinePower {
+    MachinePower::Electric {
+        usage: Fraction::from(usage),
+        drain: Fraction::new(drain.0, drain.1),
+    }
+}
+
+// All power figures are in kW, coal is in items per second.
+#[derive(Clone, Debug)]
+pub struct PowerEstimate {
+    pub producers: HashMap<ProducerType, Fraction>,
+    pub extractors: HashMap<Extractor, Fraction>,
+    pub drain: Fraction,
+    pub coal: Fraction,
+}
+impl PowerEstimate {
+    fn new() -> Self {
+        PowerEstimate {
+            producers: HashMap::new(),
+            extractors: HashMap::new(),
+            drain: Fraction::from(0),
+            coal: Fraction::from(0),
+        }
+    }
+
+    pub fn total(&self) -> Fraction {
+        self.producers
+            .values()
+            .chain(self.extractors.values())
+            .fold(Fraction::from(0), |total, power| total + *power)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.producers.is_empty() && self.extractors.is_empty() && self.coal == Fraction::from(0)
+    }
+
+    // Returns the electric draw of the machines, which is zero when they burn
+    // coal (added to the coal total instead) or need no power at all. None
+    // when the numbers get too large to hold.
+    fn add(&mut self, machine_power: MachinePower, amount: Fraction, consumption: Fraction) -> Option<Fraction> {
+        match machine_power {
+            MachinePower::Electric { usage, drain } => {
+                let idle_drain = drain.checked_mul(&amount.ceil())?;
+                self.drain = self.drain.checked_add(&idle_drain)?;
+                usage
+                    .checked_mul(&consumption)?
+                    .checked_mul(&amount)?
+                    .checked_add(&idle_drain)
+            }
+            MachinePower::Burner { usage } => {
+                let coal = usage
+                    .checked_mul(&consumption)?
+                    .checked_mul(&amount)?
+                    .checked_div(&Fraction::from(COAL_FUEL_VALUE))?;
+                self.coal = self.coal.checked_add(&coal)?;
+                Some(Fraction::from(0))
+            }
+            MachinePower::None => Some(Fraction::from(0)),
+        }
+    }
+}
+
+// Machine counts are the number of machines kept busy, so every machine that
+// has to be built (the count rounded up) adds its idle drain. None when the
+// totals get too large to hold.
+pub fn estimate_power(
+    user_settings: &UserSettings,
+    producers: &[(&Recipe, Fraction)],
+    extractors: &[(Extractor, Fraction)],
+) -> Option<PowerEstimate> {
+    let mut estimate = PowerEstimate::new();
+    for (recipe, amount) in producers {
+        let machine_power = producer_power(
+            &recipe.producer_type,
+            &user_settings.assembling_machine_level_for(recipe),
+            &user_settings.furnace_level_for(recipe),
+        );
+        let consumption = user_settings
+            .module_configuration(recipe)
+            .consumption_multiplier();
+        let power = estimate.add(machine_power, *amount, consumption)?;
+        if let MachinePower::Electric { .. } = machine_power {
+            let total = estimate
+                .producers
+                .entry(recipe.producer_type)
+                .or_insert_with(|| Fraction::from(0));
+            *total = total.checked_add(&power)?;
+        }
+    }
+    for (extractor, amount) in extractors {
+        let machine_power = extractor_power(extractor);
+        let power = estimate.add(machine_power, *amount, Fraction::from(1))?;
+        if let MachinePower::Electric { .. } = machine_power {
+            let total = estimate
+                .extractors
+                .entry(*extractor)
+                .or_insert_with(|| Fraction::from(0));
+            *total = total.checked_add(&power)?;
+        }
+    }
+    // Summed once here, so total() can't overflow later.
+    estimate
+        .producers
+        .values()
+        .chain(estimate.extractors.values())
+        .try_fold(Fraction::from(0), |total, power| total.checked_add(power))?;
+    Some(estimate)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::enums::MiningDrillLevel;
+    use crate::materials::material_database::MaterialDatabase;
+    use crate::{format, Calculator};
+
+    // One gear a second takes one assembling machine 1 (75 kW plus 2.5 kW
+    // drain), 6.4 stone furnaces burning 90 kW each and 2 ore a second.
+    #[test]
+    fn estimates_a_gear_chain() {
+        let material_database = MaterialDatabase::built_in().unwrap();
+        let calculation = Calculator::new(&material_database).items("iron gear wheel").calculate().unwrap();
+        let power = &calculation.power;
+        assert_eq!(power.producers.len(), 1);
+        assert_eq!(power.producers[&ProducerType::AssemblingMachine], Fraction::new(155u64, 2u64));
+        // Four electric mining drills at 90 kW.
+        assert_eq!(power.extractors[&Extractor::ElectricMiningDrill], Fraction::from(360));
+        assert_eq!(power.drain, Fraction::new(5u64, 2u64));
+        assert_eq!(power.total(), Fraction::new(875u64, 2u64));
+        // 6.4 * 90 kW / 4 MJ per coal.
+        assert_eq!(power.coal, Fraction::new(18u64, 125u64));
+        assert_eq!(
+            format::power_description(&calculation),
+            "Assembling Machines: **0.08 MW**\nElectric Mining Drills: **0.36 MW**\nTotal: **0.44 MW** (0.00 MW idle drain)\nCoal for burner machines: **18/125**/s"
+        );
+    }
+
+    #[test]
+    fn burns_coal_in_burner_drills() {
+        let material_database = MaterialDatabase::built_in().unwrap();
+        let user_settings = UserSettings {
+            mining_drill_level: MiningDrillLevel::Burner,
+            ..UserSettings::default()
+        };
+        let calculation = Calculator::new(&material_database)
+            .settings(user_settings)
+            .items("iron gear wheel")
+            .calculate()
+            .unwrap();
+        let power = &calculation.power;
+        assert!(power.extractors.is_empty());
+        assert_eq!(power.total(), Fraction::new(155u64, 2u64));
+        // Adds eight burner drills at 150 kW.
+        assert_eq!(power.coal, Fraction::new(18u64, 125u64) + Fraction::new(3u64, 10u64));
+    }
+
+    #[test]
+    fn adds_drain_for_every_machine_built() {
+        let mut estimate = PowerEstimate::new();
+        let power = estimate.add(electric(150, (5, 1)), Fraction::new(3u64, 2u64), Fraction::from(1));
+        assert_eq!(power, Some(Fraction::from(235)));
+        assert_eq!(estimate.drain, Fraction::from(10));
+    }
+}
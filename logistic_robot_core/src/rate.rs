@@ -0,0 +1,195 @@
+use crate::enums::TimeUnit;
+use core::fmt::Error;
+use core::fmt::Formatter;
+use fraction::{CheckedDiv, Fraction};
+use std::fmt::Display;
+
+// The highest rate accepted, in items per second. Far beyond any real
+// factory, and low enough that the numbers in a calculation stay in range.
+pub const MAX_RATE: u64 = 100_000;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RateParseError {
+    InvalidNumber(String),
+    UnknownUnit(String),
+    NotPositive(String),
+    TooLarge(String),
+}
+impl Display for RateParseError {
+    fn fmt(&self, formatter: &mut Formatter) -> Result<(), Error> {
+        match self {
+            RateParseError::InvalidNumber(rate) => write!(
+                formatter,
+                "*{}* is not a valid rate. Use a number like *5*, *0.5* or *3/2*, optionally followed by */s*, */m* or */h*",
+                rate
+            ),
+            RateParseError::UnknownUnit(unit) => write!(
+                formatter,
+                "Unknown time unit */{}*. Use */s*, */m* or */h*",
+                unit
+            ),
+            RateParseError::NotPositive(rate) => {
+                write!(formatter, "The rate *{}* has to be greater than zero", rate)
+            }
+            RateParseError::TooLarge(rate) => write!(
+                formatter,
+                "The rate *{}* is too large. Rates go up to *{}/s*",
+                rate, MAX_RATE
+            ),
+        }
+    }
+}
+
+impl TimeUnit {
+    pub fn seconds(&self) -> Fraction {
+        Fraction::from(match self {
+            TimeUnit::Second => 1,
+            TimeUnit::Minute => 60,
+            TimeUnit::Hour => 3600,
+        })
+    }
+
+    pub fn suffix(&self) -> &'static str {
+        match self {
+            TimeUnit::Second => "/s",
+            TimeUnit::Minute => "/m",
+            TimeUnit::Hour => "/h",
+        }
+    }
+}
+
+pub fn parse_time_unit(unit: &str) -> Option<TimeUnit> {
+    match &*unit.to_lowercase() {
+        "s" | "sec" | "second" | "seconds" => Some(TimeUnit::Second),
+        "m" | "min" | "minute" | "minutes" => Some(TimeUnit::Minute),
+        "h" | "hr" | "hour" | "hours" => Some(TimeUnit::Hour),
+        _ => None,
+    }
+}
+
+// Parses rates such as "5", "0.5", "3/2", "45/m" or "2700/h" into items per
+// second. Without a unit the rate is per second.
+pub fn parse_rate(rate: &str) -> Result<Fraction, RateParseError> {
+    let (amount, unit) = match rate.rsplit_once('/') {
+        Some((amount, unit)) if unit.chars().all(|c| c.is_ascii_alphabetic()) && !unit.is_empty() => {
+            match parse_time_unit(unit) {
+                Some(unit) => (amount, unit),
+                None => return Err(RateParseError::UnknownUnit(unit.to_string())),
+            }
+        }
+        _ => (rate, TimeUnit::Second),
+    };
+    let amount = match amount.split_once('/') {
+        Some((numerator, denominator)) => {
+            let numerator = parse_decimal(numerator);
+            let denominator = parse_decimal(denominator);
+            match (numerator, denominator) {
+                (Some(numerator), Some(denominator)) if denominator != Fraction::from(0) => {
+                    match numerator.checked_div(&denominator) {
+                        Some(amount) => amount,
+                        None => return Err(RateParseError::TooLarge(rate.to_string())),
+                    }
+                }
+                _ => return Err(RateParseError::InvalidNumber(rate.to_string())),
+            }
+        }
+        None => match parse_decimal(amount) {
+            Some(amount) => amount,
+            None => return Err(RateParseError::InvalidNumber(rate.to_string())),
+        },
+    };
+    if amount <= Fraction::from(0) {
+        return Err(RateParseError::NotPositive(rate.to_string()));
+    }
+    match amount.checked_div(&unit.seconds()) {
+        Some(amount) if amount <= Fraction::from(MAX_RATE) => Ok(amount),
+        _ => Err(RateParseError::TooLarge(rate.to_string())),
+    }
+}
+
+// Reads a plain decimal number exactly, rather than through a float.
+fn parse_decimal(number: &str) -> Option<Fraction> {
+    let (whole, decimals) = number.split_once('.').unwrap_or((number, ""));
+    if whole.is_empty() && decimals.is_empty() {
+        return None;
+    }
+    if !whole.chars().chain(decimals.chars()).all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let denominator = 10u64.checked_pow(decimals.len() as u32)?;
+    let numerator = format!("{}{}", whole, decimals).parse::<u64>().ok()?;
+    Some(Fraction::new(numerator, denominator))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rate(numerator: u64, denominator: u64) -> Result<Fraction, RateParseError> {
+        Ok(Fraction::new(numerator, denominator))
+    }
+
+    #[test]
+    fn parses_whole_numbers_and_decimals() {
+        assert_eq!(parse_rate("5"), rate(5, 1));
+        assert_eq!(parse_rate("0.5"), rate(1, 2));
+        assert_eq!(parse_rate(".25"), rate(1, 4));
+        assert_eq!(parse_rate("2."), rate(2, 1));
+        assert_eq!(parse_rate("0.1"), rate(1, 10));
+    }
+
+    #[test]
+    fn parses_fractions() {
+        assert_eq!(parse_rate("3/2"), rate(3, 2));
+        assert_eq!(parse_rate("1/3"), rate(1, 3));
+        assert_eq!(parse_rate("1.5/0.5"), rate(3, 1));
+    }
+
+    #[test]
+    fn converts_time_units_to_seconds() {
+        assert_eq!(parse_rate("2/s"), rate(2, 1));
+        assert_eq!(parse_rate("45/m"), rate(3, 4));
+        assert_eq!(parse_rate("2700/h"), rate(3, 4));
+        assert_eq!(parse_rate("90/min"), rate(3, 2));
+        assert_eq!(parse_rate("3/2/m"), rate(1, 40));
+        assert_eq!(parse_rate("1/H"), rate(1, 3600));
+    }
+
+    #[test]
+    fn rejects_invalid_numbers() {
+        for invalid in ["", ".", "abc", "-1", "1e3", "1/0", "1/", "/2", "1,5", "0.0000000000000000000001"] {
+            assert_eq!(
+                parse_rate(invalid),
+                Err(RateParseError::InvalidNumber(invalid.to_string())),
+                "{}",
+                invalid
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_unknown_units() {
+        assert_eq!(parse_rate("5/d"), Err(RateParseError::UnknownUnit("d".to_string())));
+        assert_eq!(parse_rate("5/week"), Err(RateParseError::UnknownUnit("week".to_string())));
+    }
+
+    #[test]
+    fn rejects_rates_that_are_not_positive() {
+        assert_eq!(parse_rate("0"), Err(RateParseError::NotPositive("0".to_string())));
+        assert_eq!(parse_rate("0/m"), Err(RateParseError::NotPositive("0/m".to_string())));
+    }
+
+    #[test]
+    fn rejects_rates_that_are_too_large() {
+        assert_eq!(parse_rate("100000"), rate(MAX_RATE, 1));
+        assert_eq!(parse_rate("6000000/m"), rate(MAX_RATE, 1));
+        for too_large in ["100001", "18446744073709551615", "6000001/m", "18446744073709551615/0.1"] {
+            assert_eq!(
+                parse_rate(too_large),
+                Err(RateParseError::TooLarge(too_large.to_string())),
+                "{}",
+                too_large
+            );
+        }
+    }
+}
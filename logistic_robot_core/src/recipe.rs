@@ -0,0 +1,36 @@
+use crate::enums::ProducerType;
+use fraction::Fraction;
+
+/// A recipe references its ingredients and products by their index in the
+/// material database, so several materials can share a single recipe.
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct Recipe {
+    pub name: String,
+    pub producer_type: ProducerType,
+    pub ingredients: Vec<(usize, Fraction)>,
+    pub products: Vec<(usize, Fraction)>,
+    pub time: Fraction,
+    pub enabled: bool,
+    pub allow_productivity: bool,
+}
+impl Recipe {
+    pub fn new(
+        name: &str,
+        producer_type: ProducerType,
+        ingredients: Vec<(usize, Fraction)>,
+        products: Vec<(usize, Fraction)>,
+        time: Fraction,
+        enabled: bool,
+        allow_productivity: bool,
+    ) -> Recipe {
+        Recipe {
+            name: name.to_string(),
+            producer_type,
+            ingredients,
+            products,
+            time,
+            enabled,
+            allow_productivity,
+        }
+    }
+}
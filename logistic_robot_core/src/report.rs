@@ -0,0 +1,284 @@
+use crate::calculator::Calculation;
+use crate::format::{module_warnings, producer_type_name};
+use crate::materials::material::Material;
+use crate::materials::material_database::MaterialDatabase;
+use crate::recipe::Recipe;
+use crate::total_raw_result::ProductionNode;
+use crate::user_settings::UserSettings;
+use fraction::{Fraction, ToPrimitive};
+use serde::Serialize;
+
+// A calculation's results as plain numbers and names, for writing out as json
+// or csv. Rates are per the settings' display unit and power is in MW. Every
+// number also comes as an exact fraction in a matching _exact field, e.g.
+// "16/5" next to 3.2.
+#[derive(Clone, Debug, Serialize)]
+pub struct CalculationReport {
+    pub unit: String,
+    pub targets: Vec<ItemRate>,
+    pub machines: Vec<MachineCount>,
+    pub raw: Vec<RawRate>,
+    pub surplus: Vec<ItemRate>,
+    pub belts: Vec<BeltCount>,
+    pub power: PowerUsage,
+    pub pollution_per_minute: f64,
+    pub pollution_per_minute_exact: String,
+    pub tree: Vec<TreeNode>,
+    // Modules set for a machine that it couldn't use, one line per reason.
+    pub module_warnings: Vec<String>,
+    pub rounded: bool,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct ItemRate {
+    pub item: String,
+    pub rate: f64,
+    pub rate_exact: String,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct MachineCount {
+    pub recipe: String,
+    pub machine: String,
+    pub count: f64,
+    pub count_exact: String,
+    pub outputs: Vec<ItemRate>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct RawRate {
+    pub item: String,
+    pub rate: f64,
+    pub rate_exact: String,
+    pub extractor: Option<String>,
+    pub extractors: Option<f64>,
+    pub extractors_exact: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct BeltCount {
+    pub item: String,
+    pub rate: f64,
+    pub rate_exact: String,
+    pub belt: String,
+    pub belts: f64,
+    pub belts_exact: String,
+    pub lanes: f64,
+    pub lanes_exact: String,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct PowerUsage {
+    pub total_mw: f64,
+    pub total_mw_exact: String,
+    pub drain_mw: f64,
+    pub drain_mw_exact: String,
+    // Coal burnt by burner machines, per display unit.
+    pub coal: f64,
+    pub coal_exact: String,
+}
+
+// One item in the recipe graph, with the machines making it for this branch
+// and the ingredients they need. Repeated nodes are made further up the same
+// branch and have no children.
+#[derive(Clone, Debug, Serialize)]
+pub struct TreeNode {
+    pub item: String,
+    pub rate: f64,
+    pub rate_exact: String,
+    pub machines: Vec<MachineCount>,
+    pub repeated: bool,
+    pub children: Vec<TreeNode>,
+}
+
+// What the info command shows about an item: its other names and every
+// recipe making it, including those disabled by default.
+#[derive(Clone, Debug, Serialize)]
+pub struct ItemInfo {
+    pub name: String,
+    pub aliases: Vec<String>,
+    pub fluid: bool,
+    pub raw: bool,
+    pub extractor: Option<String>,
+    pub recipes: Vec<RecipeInfo>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct RecipeInfo {
+    pub name: String,
+    pub machine: String,
+    pub time: f64,
+    pub time_exact: String,
+    pub enabled: bool,
+    pub ingredients: Vec<ItemAmount>,
+    pub products: Vec<ItemAmount>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct ItemAmount {
+    pub item: String,
+    pub amount: f64,
+    pub amount_exact: String,
+}
+
+impl ItemInfo {
+    pub fn new(material_database: &MaterialDatabase, material: &Material) -> Self {
+        let item_amounts = |resources: &[(usize, Fraction)]| -> Vec<ItemAmount> {
+            resources
+                .iter()
+                .map(|(material_index, amount)| ItemAmount {
+                    item: material_database.material(*material_index).name.clone(),
+                    amount: to_f64(*amount),
+                    amount_exact: amount.to_string(),
+                })
+                .collect()
+        };
+        ItemInfo {
+            name: material.name.clone(),
+            aliases: material.aliases.clone(),
+            fluid: material.fluid,
+            raw: material.recipes.is_empty(),
+            extractor: material
+                .extraction
+                .as_ref()
+                .map(|extraction| extraction.extractor_type.to_string()),
+            recipes: material
+                .recipes
+                .iter()
+                .map(|&recipe_index| {
+                    let recipe = material_database.recipe(recipe_index);
+                    RecipeInfo {
+                        name: recipe.name.clone(),
+                        machine: producer_type_name(recipe.producer_type, false),
+                        time: to_f64(recipe.time),
+                        time_exact: recipe.time.to_string(),
+                        enabled: recipe.enabled,
+                        ingredients: item_amounts(&recipe.ingredients),
+                        products: item_amounts(&recipe.products),
+                    }
+                })
+                .collect(),
+        }
+    }
+}
+
+impl CalculationReport {
+    pub fn new(calculation: &Calculation) -> Self {
+        let user_settings = &calculation.settings;
+        let seconds = user_settings.display_unit.seconds();
+        let belt_tier = user_settings.belt_tier;
+        let total_mw = calculation.power.total() / Fraction::from(1000);
+        let drain_mw = calculation.power.drain / Fraction::from(1000);
+        let coal = calculation.power.coal * seconds;
+        CalculationReport {
+            unit: user_settings.display_unit.to_string().to_lowercase(),
+            targets: calculation
+                .targets
+                .iter()
+                .map(|(material, rate)| item_rate(material, *rate * calculation.ratio * seconds))
+                .collect(),
+            machines: calculation
+                .producers
+                .iter()
+                .map(|(recipe, machines)| machine_count(calculation, recipe, *machines))
+                .collect(),
+            raw: calculation
+                .total_raw
+                .iter()
+                .map(|(material, amount, extractor)| RawRate {
+                    item: material.name.clone(),
+                    rate: to_f64(*amount * seconds),
+                    rate_exact: (*amount * seconds).to_string(),
+                    extractor: extractor.map(|(extractor, _count)| extractor.to_string()),
+                    extractors: extractor.map(|(_extractor, count)| to_f64(count)),
+                    extractors_exact: extractor.map(|(_extractor, count)| count.to_string()),
+                })
+                .collect(),
+            surplus: calculation
+                .surplus
+                .iter()
+                .map(|(material, amount)| item_rate(material, *amount * seconds))
+                .collect(),
+            belts: calculation
+                .item_rates
+                .iter()
+                .map(|(material, rate)| BeltCount {
+                    item: material.name.clone(),
+                    rate: to_f64(*rate * seconds),
+                    rate_exact: (*rate * seconds).to_string(),
+                    belt: belt_tier.to_string(),
+                    belts: to_f64(belt_tier.belts_required(*rate)),
+                    belts_exact: belt_tier.belts_required(*rate).to_string(),
+                    lanes: to_f64(belt_tier.lanes_required(*rate)),
+                    lanes_exact: belt_tier.lanes_required(*rate).to_string(),
+                })
+                .collect(),
+            power: PowerUsage {
+                total_mw: to_f64(total_mw),
+                total_mw_exact: total_mw.to_string(),
+                drain_mw: to_f64(drain_mw),
+                drain_mw_exact: drain_mw.to_string(),
+                coal: to_f64(coal),
+                coal_exact: coal.to_string(),
+            },
+            pollution_per_minute: to_f64(calculation.pollution.total()),
+            pollution_per_minute_exact: calculation.pollution.total().to_string(),
+            tree: calculation
+                .tree
+                .iter()
+                .map(|node| tree_node(calculation, user_settings, node))
+                .collect(),
+            module_warnings: module_warnings(calculation)
+                .map(|warnings| warnings.lines().map(|line| line.to_string()).collect())
+                .unwrap_or_default(),
+            rounded: calculation.rounded,
+        }
+    }
+}
+
+fn tree_node(calculation: &Calculation, user_settings: &UserSettings, node: &ProductionNode) -> TreeNode {
+    TreeNode {
+        item: node.material.name.clone(),
+        rate: to_f64(node.rate * user_settings.display_unit.seconds()),
+        rate_exact: (node.rate * user_settings.display_unit.seconds()).to_string(),
+        machines: node
+            .producers
+            .iter()
+            .map(|(recipe, machines)| machine_count(calculation, recipe, *machines))
+            .collect(),
+        repeated: node.repeated,
+        children: node
+            .children
+            .iter()
+            .map(|child| tree_node(calculation, user_settings, child))
+            .collect(),
+    }
+}
+
+fn machine_count(calculation: &Calculation, recipe: &Recipe, machines: Fraction) -> MachineCount {
+    let seconds = calculation.settings.display_unit.seconds();
+    MachineCount {
+        recipe: recipe.name.clone(),
+        machine: producer_type_name(recipe.producer_type, false),
+        count: to_f64(machines),
+        count_exact: machines.to_string(),
+        outputs: Material::calculate_output_rates(&calculation.settings, recipe, machines)
+            .into_iter()
+            .map(|(material_index, rate)| {
+                item_rate(calculation.material_database().material(material_index), rate * seconds)
+            })
+            .collect(),
+    }
+}
+
+fn item_rate(material: &Material, rate: Fraction) -> ItemRate {
+    ItemRate {
+        item: material.name.clone(),
+        rate: to_f64(rate),
+        rate_exact: rate.to_string(),
+    }
+}
+
+fn to_f64(fraction: Fraction) -> f64 {
+    fraction.to_f64().unwrap_or(0.0)
+}
@@ -0,0 +1,452 @@
+pub mod production_tree;
+pub mod simplex;
+pub mod sparse_matrix;
+
+use crate::materials::material::Material;
+use crate::materials::material_database::MaterialDatabase;
+use crate::recipe::Recipe;
+use crate::solver::production_tree::ProductionTreeBuilder;
+use crate::solver::sparse_matrix::SparseMatrix;
+use crate::total_raw_result::{ProductionNode, TotalRawResult};
+use crate::user_settings::UserSettings;
+use core::fmt::Error;
+use core::fmt::Formatter;
+use fraction::convert::TryToConvertFrom;
+use fraction::{BigFraction, BigUint, Fraction, One, ToPrimitive, Zero};
+use std::collections::{HashMap, VecDeque};
+use std::fmt::Display;
+
+// Largest denominator kept when a result does not fit in a `Fraction` and has
+// to be rounded.
+const MAX_ROUNDED_DENOMINATOR: u64 = 1_000_000;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SolverError {
+    Infeasible,
+    Unbounded,
+    NotFinite,
+    TooLarge,
+    NoWholeRatio,
+}
+impl Display for SolverError {
+    fn fmt(&self, formatter: &mut Formatter) -> Result<(), Error> {
+        write!(
+            formatter,
+            "{}",
+            match self {
+                SolverError::Infeasible => "No combination of recipes can produce this item.",
+                SolverError::Unbounded => "The recipes for this item form an unbounded loop.",
+                SolverError::NotFinite => "A recipe or setting divides by zero, so there is nothing to solve.",
+                SolverError::TooLarge => "The numbers involved are too large to work with. Try a smaller rate.",
+                SolverError::NoWholeRatio => {
+                    "I can't find a whole number ratio of machines this small. Try again without whole numbers."
+                }
+            }
+        )
+    }
+}
+
+enum Column {
+    Recipe(usize),
+    RawSupply(usize),
+    Surplus(usize),
+}
+
+// Finds non-negative recipe rates meeting every target rate, first minimising
+// the total raw input (weighted by each raw material's cost) and then the
+// total number of crafts among the solutions with that raw input. Each row of
+// the matrix is one material's net production.
+pub fn solve<'a>(
+    material_database: &'a MaterialDatabase,
+    targets: &[(usize, Fraction)],
+    user_settings: &UserSettings,
+) -> Result<TotalRawResult<'a>, SolverError> {
+    // Imported materials come from elsewhere (e.g. a main bus), so they are
+    // supplied like raw materials unless they are one of the targets.
+    let is_raw = |material_index: usize| {
+        let material = material_database.material(material_index);
+        material.recipes.is_empty()
+            || (user_settings.is_imported(material)
+                && !targets.iter().any(|(target_index, _amount)| *target_index == material_index))
+    };
+    // A recipe the user picked for a material replaces every other recipe
+    // making it, and is used even when it is disabled by default.
+    let is_allowed = |recipe: &Recipe| {
+        let mut chosen = false;
+        for (material_index, _amount) in &recipe.products {
+            match user_settings.recipe_choice(material_database.material(*material_index)) {
+                Some(choice) if choice.to_lowercase() == recipe.name.to_lowercase() => chosen = true,
+                Some(_choice) => return false,
+                None => {}
+            }
+        }
+        recipe.enabled || chosen
+    };
+    let (material_indices, recipe_indices) =
+        collect_relevant(material_database, targets, &is_raw, &is_allowed);
+    let row_of: HashMap<usize, usize> = material_indices
+        .iter()
+        .enumerate()
+        .map(|(row, &material_index)| (material_index, row))
+        .collect();
+
+    let mut constraints = SparseMatrix::new(material_indices.len());
+    let mut columns: Vec<Column> = Vec::new();
+    for &recipe_index in &recipe_indices {
+        let recipe = material_database.recipe(recipe_index);
+        let productivity = Material::calculate_productivity_modifier(user_settings, recipe);
+        let entries = recipe
+            .products
+            .iter()
+            .map(|(material_index, amount)| Ok((row_of[material_index], to_big(*amount * productivity)?)))
+            .chain(
+                recipe
+                    .ingredients
+                    .iter()
+                    .map(|(material_index, amount)| Ok((row_of[material_index], -to_big(*amount)?))),
+            )
+            .collect::<Result<Vec<(usize, BigFraction)>, SolverError>>()?;
+        constraints.add_column(entries);
+        columns.push(Column::Recipe(recipe_index));
+    }
+    for (row, &material_index) in material_indices.iter().enumerate() {
+        if is_raw(material_index) {
+            constraints.add_column(vec![(row, BigFraction::one())]);
+            columns.push(Column::RawSupply(material_index));
+        }
+    }
+    // Imported materials keep their surplus column, since a recipe in the
+    // chain may still make them as a by-product.
+    for (row, &material_index) in material_indices.iter().enumerate() {
+        if !material_database.material(material_index).recipes.is_empty() {
+            constraints.add_column(vec![(row, -BigFraction::one())]);
+            columns.push(Column::Surplus(material_index));
+        }
+    }
+
+    let mut rhs = vec![BigFraction::zero(); material_indices.len()];
+    for (material_index, amount) in targets {
+        let row = row_of[material_index];
+        rhs[row] = &rhs[row] + &to_big(*amount)?;
+    }
+    let raw_costs = columns
+        .iter()
+        .map(|column| match column {
+            Column::RawSupply(material_index) => to_big(material_database.material(*material_index).raw_cost),
+            _ => Ok(BigFraction::zero()),
+        })
+        .collect::<Result<Vec<BigFraction>, SolverError>>()?;
+    let craft_costs = columns
+        .iter()
+        .map(|column| match column {
+            Column::Recipe(_) => BigFraction::one(),
+            _ => BigFraction::zero(),
+        })
+        .collect();
+
+    let solution = simplex::minimize_lexicographic(&constraints, &rhs, &[raw_costs, craft_costs])?;
+
+    let mut rounded = false;
+    let mut producers: HashMap<&Recipe, Fraction> = HashMap::new();
+    let mut total_raw: HashMap<&Material, Fraction> = HashMap::new();
+    let mut surplus: HashMap<&Material, Fraction> = HashMap::new();
+    let mut crafts: HashMap<usize, BigFraction> = HashMap::new();
+    let mut production: HashMap<usize, BigFraction> = HashMap::new();
+    for (column, value) in columns.iter().zip(solution) {
+        if value.is_zero() {
+            continue;
+        }
+        match column {
+            Column::Recipe(recipe_index) => {
+                let recipe = material_database.recipe(*recipe_index);
+                let productivity = Material::calculate_productivity_modifier(user_settings, recipe);
+                for (material_index, amount) in &recipe.products {
+                    let rate = production
+                        .entry(*material_index)
+                        .or_insert_with(BigFraction::zero);
+                    *rate = &*rate + &(&value * &to_big(*amount * productivity)?);
+                }
+                crafts.insert(*recipe_index, value.clone());
+                let production_modifier =
+                    Material::calculate_production_modifier(user_settings, recipe);
+                let machines = &value * &to_big(recipe.time / production_modifier)?;
+                producers.insert(recipe, from_big(&machines, &mut rounded));
+            }
+            // An imported material can also be a by-product of the chain, so
+            // its supply adds to whatever recipes already make.
+            Column::RawSupply(material_index) => {
+                let rate = production
+                    .entry(*material_index)
+                    .or_insert_with(BigFraction::zero);
+                *rate = &*rate + &value;
+                total_raw.insert(
+                    material_database.material(*material_index),
+                    from_big(&value, &mut rounded),
+                );
+            }
+            Column::Surplus(material_index) => {
+                surplus.insert(
+                    material_database.material(*material_index),
+                    from_big(&value, &mut rounded),
+                );
+            }
+        }
+    }
+    let mut extractors = HashMap::new();
+    for (&material, &rate) in &total_raw {
+        if let Some(extraction) = &material.extraction {
+            match extraction.extractor_count(rate, user_settings) {
+                Some((extractor, count)) if count.is_finite() => {
+                    extractors.insert(material, (extractor, count));
+                }
+                Some(_) => return Err(SolverError::NotFinite),
+                None => return Err(SolverError::TooLarge),
+            }
+        }
+    }
+    let mut result = TotalRawResult::new(producers, total_raw, surplus);
+    result.extractors = extractors;
+    result.item_rates = production
+        .iter()
+        .map(|(&material_index, rate)| {
+            (material_database.material(material_index), from_big(rate, &mut rounded))
+        })
+        .collect();
+    let mut tree_builder = ProductionTreeBuilder {
+        material_database,
+        user_settings,
+        crafts: &crafts,
+        production: &production,
+        rounded,
+    };
+    result.tree = targets
+        .iter()
+        .map(|(material_index, amount)| {
+            tree_builder.build(*material_index, &to_big(*amount)?, &mut Vec::new())
+        })
+        .collect::<Result<Vec<ProductionNode>, SolverError>>()?;
+    result.rounded = tree_builder.rounded;
+    Ok(result)
+}
+
+// Walks every allowed recipe that could contribute to the targets, collecting
+// the materials they touch (including co-products) and the recipes themselves.
+fn collect_relevant(
+    material_database: &MaterialDatabase,
+    targets: &[(usize, Fraction)],
+    is_raw: &dyn Fn(usize) -> bool,
+    is_allowed: &dyn Fn(&Recipe) -> bool,
+) -> (Vec<usize>, Vec<usize>) {
+    let mut material_indices: Vec<usize> = Vec::new();
+    let mut recipe_indices: Vec<usize> = Vec::new();
+    let mut queue: VecDeque<usize> = targets.iter().map(|(index, _amount)| *index).collect();
+    while let Some(material_index) = queue.pop_front() {
+        if material_indices.contains(&material_index) {
+            continue;
+        }
+        material_indices.push(material_index);
+        if is_raw(material_index) {
+            continue;
+        }
+        for &recipe_index in &material_database.material(material_index).recipes {
+            let recipe = material_database.recipe(recipe_index);
+            if !is_allowed(recipe) || recipe_indices.contains(&recipe_index) {
+                continue;
+            }
+            recipe_indices.push(recipe_index);
+            queue.extend(
+                recipe
+                    .ingredients
+                    .iter()
+                    .chain(recipe.products.iter())
+                    .map(|(index, _amount)| *index),
+            );
+        }
+    }
+    (material_indices, recipe_indices)
+}
+
+// Only infinite and NaN fractions fail to convert, which come from dividing
+// by zero somewhere in the recipe data or settings.
+fn to_big(fraction: Fraction) -> Result<BigFraction, SolverError> {
+    BigFraction::try_to_convert_from(fraction).ok_or(SolverError::NotFinite)
+}
+
+// Converts back to a `Fraction`, falling back to the closest fraction with a
+// small denominator (and flagging it) when the exact value does not fit.
+fn from_big(value: &BigFraction, rounded: &mut bool) -> Fraction {
+    if let Some(fraction) = Fraction::try_to_convert_from(value.clone()) {
+        return fraction;
+    }
+    *rounded = true;
+    let numerator = value.numer().cloned().unwrap_or_else(BigUint::zero);
+    let denominator = value.denom().cloned().unwrap_or_else(BigUint::one);
+    let approximation = best_approximation(numerator, denominator);
+    if value < &BigFraction::zero() {
+        -approximation
+    } else {
+        approximation
+    }
+}
+
+// Walks the continued fraction expansion of `numerator / denominator` until
+// the next convergent would exceed `MAX_ROUNDED_DENOMINATOR`.
+fn best_approximation(mut numerator: BigUint, mut denominator: BigUint) -> Fraction {
+    let (mut previous_numerator, mut current_numerator) = (0u64, 1u64);
+    let (mut previous_denominator, mut current_denominator) = (1u64, 0u64);
+    while !denominator.is_zero() {
+        let term = match (&numerator / &denominator).to_u64() {
+            Some(term) => term,
+            None => break,
+        };
+        let next_numerator = term
+            .checked_mul(current_numerator)
+            .and_then(|product| product.checked_add(previous_numerator));
+        let next_denominator = term
+            .checked_mul(current_denominator)
+            .and_then(|product| product.checked_add(previous_denominator));
+        match (next_numerator, next_denominator) {
+            (Some(next_numerator), Some(next_denominator))
+                if next_denominator <= MAX_ROUNDED_DENOMINATOR =>
+            {
+                previous_numerator = current_numerator;
+                current_numerator = next_numerator;
+                previous_denominator = current_denominator;
+                current_denominator = next_denominator;
+            }
+            _ => break,
+        }
+        let remainder = &numerator % &denominator;
+        numerator = denominator;
+        denominator = remainder;
+    }
+    if current_denominator == 0 {
+        return Fraction::from(u64::MAX);
+    }
+    Fraction::new(current_numerator, current_denominator)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn material_database() -> MaterialDatabase {
+        MaterialDatabase::built_in().unwrap()
+    }
+
+    fn solve_for<'a>(
+        material_database: &'a MaterialDatabase,
+        targets: &[(&str, Fraction)],
+        user_settings: &UserSettings,
+    ) -> Result<TotalRawResult<'a>, SolverError> {
+        let targets: Vec<(&Material, Fraction)> = targets
+            .iter()
+            .map(|(name, rate)| (material_database.find_material(name).unwrap(), *rate))
+            .collect();
+        material_database.total_raw_result(&targets, user_settings)
+    }
+
+    fn rate_of(rates: &HashMap<&Material, Fraction>, name: &str) -> Fraction {
+        rates
+            .iter()
+            .find(|(material, _rate)| material.name == name)
+            .map(|(_material, rate)| *rate)
+            .unwrap_or_else(|| Fraction::from(0))
+    }
+
+    fn machines_for(result: &TotalRawResult, recipe_name: &str) -> Fraction {
+        result
+            .producers
+            .iter()
+            .find(|(recipe, _machines)| recipe.name == recipe_name)
+            .map(|(_recipe, machines)| *machines)
+            .unwrap_or_else(|| Fraction::from(0))
+    }
+
+    #[test]
+    fn solves_a_plain_chain() {
+        let material_database = material_database();
+        let result = solve_for(&material_database, &[("Iron Gear Wheel", Fraction::from(1))], &UserSettings::default())
+            .unwrap();
+        assert_eq!(result.producers.len(), 2);
+        assert_eq!(machines_for(&result, "Iron Gear Wheel"), Fraction::from(1));
+        assert_eq!(machines_for(&result, "Iron Plate"), Fraction::new(32u64, 5u64));
+        assert_eq!(result.total_raw.len(), 1);
+        assert_eq!(rate_of(&result.total_raw, "Iron Ore"), Fraction::from(2));
+        assert!(result.surplus.values().all(|amount| *amount == Fraction::from(0)));
+        assert!(!result.rounded);
+    }
+
+    // Kovarex turns uranium-238 into uranium-235 and feeds on its own output,
+    // so the least ore is used when every bit of uranium-238 is enriched.
+    #[test]
+    fn solves_a_cycle() {
+        let material_database = material_database();
+        let result = solve_for(&material_database, &[("Uranium-235", Fraction::from(1))], &UserSettings::default())
+            .unwrap();
+        assert_eq!(rate_of(&result.total_raw, "Uranium Ore"), Fraction::new(5000u64, 169u64));
+        assert_eq!(rate_of(&result.surplus, "Uranium-238"), Fraction::from(0));
+        assert!(machines_for(&result, "Kovarex Enrichment Process") > Fraction::from(0));
+        // Kovarex's own uranium-235 output counts towards what's made.
+        assert!(rate_of(&result.item_rates, "Uranium-235") > Fraction::from(1));
+    }
+
+    #[test]
+    fn keeps_by_products_as_surplus() {
+        let material_database = material_database();
+        let mut user_settings = UserSettings::default();
+        let advanced_oil_processing = material_database.lookup_recipe("Advanced Oil Processing").unwrap();
+        user_settings.choose_recipe(advanced_oil_processing, &material_database);
+        let result = solve_for(&material_database, &[("Petroleum Gas", Fraction::from(11))], &user_settings).unwrap();
+        assert_eq!(machines_for(&result, "Advanced Oil Processing"), Fraction::from(1));
+        assert_eq!(rate_of(&result.total_raw, "Crude Oil"), Fraction::from(20));
+        assert_eq!(rate_of(&result.total_raw, "Water"), Fraction::from(10));
+        assert_eq!(rate_of(&result.surplus, "Heavy Oil"), Fraction::from(5));
+        assert_eq!(rate_of(&result.surplus, "Light Oil"), Fraction::from(9));
+        assert_eq!(rate_of(&result.surplus, "Petroleum Gas"), Fraction::from(0));
+    }
+
+    #[test]
+    fn skips_disabled_recipes_unless_chosen() {
+        let material_database = material_database();
+        let mut user_settings = UserSettings::default();
+        let result = solve_for(&material_database, &[("Heavy Oil", Fraction::from(9))], &user_settings).unwrap();
+        assert_eq!(machines_for(&result, "Coal Liquefaction"), Fraction::from(0));
+
+        let coal_liquefaction = material_database.lookup_recipe("Coal Liquefaction").unwrap();
+        user_settings.choose_recipe(coal_liquefaction, &material_database);
+        let result = solve_for(&material_database, &[("Heavy Oil", Fraction::from(9))], &user_settings).unwrap();
+        assert!(machines_for(&result, "Coal Liquefaction") > Fraction::from(0));
+        assert_eq!(rate_of(&result.total_raw, "Crude Oil"), Fraction::from(0));
+    }
+
+    #[test]
+    fn fails_without_an_allowed_recipe() {
+        let material_database = material_database();
+        let mut user_settings = UserSettings::default();
+        user_settings
+            .recipe_choices
+            .insert("iron plate".to_string(), "No Such Recipe".to_string());
+        let result = solve_for(&material_database, &[("Iron Gear Wheel", Fraction::from(1))], &user_settings);
+        assert_eq!(result.err(), Some(SolverError::Infeasible));
+    }
+
+    // Advanced oil processing makes 5 heavy oil alongside the petroleum gas,
+    // and the other 5 the lubricant needs are imported.
+    #[test]
+    fn counts_imported_by_products_in_item_rates() {
+        let material_database = material_database();
+        let mut user_settings = UserSettings::default();
+        let advanced_oil_processing = material_database.lookup_recipe("Advanced Oil Processing").unwrap();
+        user_settings.choose_recipe(advanced_oil_processing, &material_database);
+        user_settings.imports.push("Heavy Oil".to_string());
+        let result = solve_for(
+            &material_database,
+            &[("Petroleum Gas", Fraction::from(11)), ("Lubricant", Fraction::from(10))],
+            &user_settings,
+        )
+        .unwrap();
+        assert_eq!(rate_of(&result.total_raw, "Heavy Oil"), Fraction::from(5));
+        assert_eq!(rate_of(&result.item_rates, "Heavy Oil"), Fraction::from(10));
+    }
+}
@@ -0,0 +1,187 @@
+use crate::materials::material::Material;
+use crate::materials::material_database::MaterialDatabase;
+use crate::solver::{from_big, to_big, SolverError};
+use crate::total_raw_result::ProductionNode;
+use crate::user_settings::UserSettings;
+use fraction::{BigFraction, Zero};
+use std::collections::HashMap;
+
+pub struct ProductionTreeBuilder<'a, 'b> {
+    pub material_database: &'a MaterialDatabase,
+    pub user_settings: &'b UserSettings,
+    // Crafts per second of every recipe in the solution.
+    pub crafts: &'b HashMap<usize, BigFraction>,
+    // Items per second made of each material, across all of its recipes.
+    pub production: &'b HashMap<usize, BigFraction>,
+    pub rounded: bool,
+}
+impl<'a, 'b> ProductionTreeBuilder<'a, 'b> {
+    // A branch asking for `rate` of a material gets each of its recipes in
+    // proportion to how much of the material that recipe makes overall. A
+    // material already being made further up the branch (e.g. the uranium-235
+    // fed back into kovarex enrichment) is not expanded again. Recipes with
+    // several products show up under each product they are needed for.
+    pub fn build(
+        &mut self,
+        material_index: usize,
+        rate: &BigFraction,
+        path: &mut Vec<usize>,
+    ) -> Result<ProductionNode<'a>, SolverError> {
+        let material_database = self.material_database;
+        let material = material_database.material(material_index);
+        let repeated = path.contains(&material_index);
+        let total_production = self.production.get(&material_index);
+        let mut node = ProductionNode {
+            material,
+            rate: from_big(rate, &mut self.rounded),
+            producers: Vec::new(),
+            children: Vec::new(),
+            repeated,
+        };
+        let total_production = match total_production {
+            Some(total_production) if !repeated && !total_production.is_zero() => total_production,
+            _ => return Ok(node),
+        };
+
+        let mut child_rates: Vec<(usize, BigFraction)> = Vec::new();
+        for &recipe_index in &material.recipes {
+            let recipe_crafts = match self.crafts.get(&recipe_index) {
+                Some(recipe_crafts) => recipe_crafts,
+                None => continue,
+            };
+            let recipe = material_database.recipe(recipe_index);
+            let crafts = &(rate * recipe_crafts) / total_production;
+            let production_modifier = Material::calculate_production_modifier(self.user_settings, recipe);
+            let machines = &crafts * &to_big(recipe.time / production_modifier)?;
+            node.producers.push((recipe, from_big(&machines, &mut self.rounded)));
+            for (ingredient_index, amount) in &recipe.ingredients {
+                let ingredient_rate = &crafts * &to_big(*amount)?;
+                match child_rates.iter_mut().find(|(index, _rate)| index == ingredient_index) {
+                    Some((_index, child_rate)) => *child_rate = &*child_rate + &ingredient_rate,
+                    None => child_rates.push((*ingredient_index, ingredient_rate)),
+                }
+            }
+        }
+
+        path.push(material_index);
+        node.children = child_rates
+            .iter()
+            .map(|(ingredient_index, ingredient_rate)| self.build(*ingredient_index, ingredient_rate, path))
+            .collect::<Result<Vec<ProductionNode>, SolverError>>()?;
+        path.pop();
+        Ok(node)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::materials::material_database::MaterialDatabase;
+    use crate::total_raw_result::ProductionNode;
+    use crate::user_settings::UserSettings;
+    use fraction::Fraction;
+
+    fn tree<'a>(material_database: &'a MaterialDatabase, name: &str) -> ProductionNode<'a> {
+        let material = material_database.find_material(name).unwrap();
+        let result = material_database
+            .total_raw_result(&[(material, Fraction::from(1))], &UserSettings::default())
+            .unwrap();
+        result.tree.into_iter().next().unwrap()
+    }
+
+    fn child<'n, 'a>(node: &'n ProductionNode<'a>, name: &str) -> &'n ProductionNode<'a> {
+        node.children.iter().find(|child| child.material.name == name).unwrap()
+    }
+
+    fn children(node: &ProductionNode) -> Vec<(String, Fraction)> {
+        node.children
+            .iter()
+            .map(|child| (child.material.name.clone(), child.rate))
+            .collect()
+    }
+
+    fn producers(node: &ProductionNode) -> Vec<(String, Fraction)> {
+        node.producers
+            .iter()
+            .map(|(recipe, machines)| (recipe.name.clone(), *machines))
+            .collect()
+    }
+
+    fn named(name: &str, numerator: u64, denominator: u64) -> (String, Fraction) {
+        (name.to_string(), Fraction::new(numerator, denominator))
+    }
+
+    #[test]
+    fn splits_a_chain_into_branches() {
+        let material_database = MaterialDatabase::built_in().unwrap();
+        let logistic_science_pack = tree(&material_database, "Logistic Science Pack");
+        assert_eq!(logistic_science_pack.rate, Fraction::from(1));
+        assert_eq!(producers(&logistic_science_pack), vec![named("Logistic Science Pack", 12, 1)]);
+        assert_eq!(
+            children(&logistic_science_pack),
+            vec![named("Inserter", 1, 1), named("Transport Belt", 1, 1)]
+        );
+
+        let inserter = child(&logistic_science_pack, "Inserter");
+        assert_eq!(producers(inserter), vec![named("Inserter", 1, 1)]);
+        assert_eq!(
+            children(inserter),
+            vec![named("Electronic Circuit", 1, 1), named("Iron Gear Wheel", 1, 1), named("Iron Plate", 1, 1)]
+        );
+
+        let electronic_circuit = child(inserter, "Electronic Circuit");
+        assert_eq!(producers(electronic_circuit), vec![named("Electronic Circuit", 1, 1)]);
+        assert_eq!(children(electronic_circuit), vec![named("Copper Cable", 3, 1), named("Iron Plate", 1, 1)]);
+
+        let copper_cable = child(electronic_circuit, "Copper Cable");
+        assert_eq!(producers(copper_cable), vec![named("Copper Cable", 3, 2)]);
+        assert_eq!(children(copper_cable), vec![named("Copper Plate", 3, 2)]);
+        let copper_plate = child(copper_cable, "Copper Plate");
+        assert_eq!(producers(copper_plate), vec![named("Copper Plate", 24, 5)]);
+        assert_eq!(children(copper_plate), vec![named("Copper Ore", 3, 2)]);
+        let copper_ore = child(copper_plate, "Copper Ore");
+        assert!(copper_ore.producers.is_empty() && copper_ore.children.is_empty());
+        assert!(!copper_ore.repeated);
+
+        let transport_belt = child(&logistic_science_pack, "Transport Belt");
+        assert_eq!(producers(transport_belt), vec![named("Transport Belt", 1, 2)]);
+        assert_eq!(
+            children(transport_belt),
+            vec![named("Iron Gear Wheel", 1, 2), named("Iron Plate", 1, 2)]
+        );
+    }
+
+    fn depth(node: &ProductionNode) -> usize {
+        1 + node.children.iter().map(depth).max().unwrap_or(0)
+    }
+
+    fn assert_repeats_are_leaves(node: &ProductionNode) {
+        if node.repeated {
+            assert!(node.producers.is_empty() && node.children.is_empty());
+        }
+        node.children.iter().for_each(assert_repeats_are_leaves);
+    }
+
+    #[test]
+    fn stops_at_materials_fed_back_into_their_own_recipe() {
+        let material_database = MaterialDatabase::built_in().unwrap();
+        let uranium_235 = tree(&material_database, "Uranium-235");
+        assert_eq!(
+            producers(&uranium_235).iter().map(|(name, _machines)| name.as_str()).collect::<Vec<&str>>(),
+            vec!["Uranium Processing", "Kovarex Enrichment Process"]
+        );
+        assert!(child(&uranium_235, "Uranium-235").repeated);
+        let uranium_238 = child(&uranium_235, "Uranium-238");
+        assert!(!uranium_238.repeated);
+        assert!(child(uranium_238, "Uranium-238").repeated);
+        assert_eq!(depth(&uranium_235), 3);
+        assert_repeats_are_leaves(&uranium_235);
+
+        let plastic_bar = tree(&material_database, "Plastic Bar");
+        let petroleum_gas = child(&plastic_bar, "Petroleum Gas");
+        assert_eq!(
+            producers(petroleum_gas),
+            vec![named("Advanced Oil Processing", 20, 39), named("Light Oil Cracking", 17, 39)]
+        );
+        assert_repeats_are_leaves(&plastic_bar);
+    }
+}
@@ -0,0 +1,282 @@
+use crate::solver::sparse_matrix::SparseMatrix;
+use crate::solver::SolverError;
+use fraction::{BigFraction, One, Zero};
+
+// Dense two-phase simplex over exact fractions. Bland's rule is used for both
+// the entering and leaving variable so degenerate problems cannot cycle.
+struct Tableau {
+    rows: Vec<Vec<BigFraction>>,
+    objective: Vec<BigFraction>,
+    basis: Vec<usize>,
+    allowed: Vec<bool>,
+}
+
+// Solves `constraints * x = rhs` for `x >= 0`, minimising each objective in
+// turn while keeping every earlier objective at its optimum.
+pub fn minimize_lexicographic(
+    constraints: &SparseMatrix,
+    rhs: &[BigFraction],
+    objectives: &[Vec<BigFraction>],
+) -> Result<Vec<BigFraction>, SolverError> {
+    let column_count = constraints.column_count();
+    let row_count = constraints.row_count();
+    let mut tableau = Tableau::new(constraints, rhs);
+
+    let phase_one_costs: Vec<BigFraction> = (0..column_count + row_count)
+        .map(|column| {
+            if column < column_count {
+                BigFraction::zero()
+            } else {
+                BigFraction::one()
+            }
+        })
+        .collect();
+    tableau.set_objective(&phase_one_costs);
+    tableau.optimize()?;
+    if !tableau.objective_value().is_zero() {
+        return Err(SolverError::Infeasible);
+    }
+    tableau.remove_artificial_columns(column_count);
+
+    for costs in objectives {
+        tableau.set_objective(costs);
+        tableau.optimize()?;
+        tableau.fix_suboptimal_columns();
+    }
+    Ok(tableau.solution(column_count))
+}
+
+impl Tableau {
+    fn new(constraints: &SparseMatrix, rhs: &[BigFraction]) -> Self {
+        let column_count = constraints.column_count();
+        let row_count = constraints.row_count();
+        let mut rows = constraints.to_dense();
+        for (row_index, row) in rows.iter_mut().enumerate() {
+            let mut value = rhs[row_index].clone();
+            // Every right hand side has to be non-negative for the artificial
+            // variables to form a feasible starting basis.
+            if value < BigFraction::zero() {
+                for entry in row.iter_mut() {
+                    *entry = -&*entry;
+                }
+                value = -value;
+            }
+            for artificial in 0..row_count {
+                row.push(if artificial == row_index {
+                    BigFraction::one()
+                } else {
+                    BigFraction::zero()
+                });
+            }
+            row.push(value);
+        }
+        Tableau {
+            rows,
+            objective: Vec::new(),
+            basis: (column_count..column_count + row_count).collect(),
+            allowed: vec![true; column_count + row_count],
+        }
+    }
+
+    fn column_count(&self) -> usize {
+        self.allowed.len()
+    }
+
+    fn set_objective(&mut self, costs: &[BigFraction]) {
+        let mut objective: Vec<BigFraction> = costs.to_vec();
+        objective.push(BigFraction::zero());
+        for (row_index, &basic_column) in self.basis.iter().enumerate() {
+            let basic_cost = &costs[basic_column];
+            if basic_cost.is_zero() {
+                continue;
+            }
+            for (cost, entry) in objective.iter_mut().zip(self.rows[row_index].iter()) {
+                if !entry.is_zero() {
+                    *cost = &*cost - &(basic_cost * entry);
+                }
+            }
+        }
+        self.objective = objective;
+    }
+
+    fn objective_value(&self) -> BigFraction {
+        -&self.objective[self.column_count()]
+    }
+
+    fn optimize(&mut self) -> Result<(), SolverError> {
+        loop {
+            let entering = (0..self.column_count()).find(|&column| {
+                self.allowed[column] && self.objective[column] < BigFraction::zero()
+            });
+            let entering = match entering {
+                Some(column) => column,
+                None => return Ok(()),
+            };
+
+            let rhs_column = self.column_count();
+            let mut leaving: Option<(usize, BigFraction)> = None;
+            for (row_index, row) in self.rows.iter().enumerate() {
+                if row[entering] <= BigFraction::zero() {
+                    continue;
+                }
+                let ratio = &row[rhs_column] / &row[entering];
+                let replace = match &leaving {
+                    None => true,
+                    Some((best_row, best_ratio)) => {
+                        ratio < *best_ratio
+                            || (ratio == *best_ratio
+                                && self.basis[row_index] < self.basis[*best_row])
+                    }
+                };
+                if replace {
+                    leaving = Some((row_index, ratio));
+                }
+            }
+            match leaving {
+                Some((row_index, _ratio)) => self.pivot(row_index, entering),
+                None => return Err(SolverError::Unbounded),
+            }
+        }
+    }
+
+    fn pivot(&mut self, pivot_row: usize, pivot_column: usize) {
+        let pivot_value = self.rows[pivot_row][pivot_column].clone();
+        for entry in self.rows[pivot_row].iter_mut() {
+            if !entry.is_zero() {
+                *entry = &*entry / &pivot_value;
+            }
+        }
+        let pivot_entries: Vec<(usize, BigFraction)> = self.rows[pivot_row]
+            .iter()
+            .enumerate()
+            .filter(|(_column, value)| !value.is_zero())
+            .map(|(column, value)| (column, value.clone()))
+            .collect();
+
+        let eliminate = |row: &mut Vec<BigFraction>| {
+            let factor = row[pivot_column].clone();
+            if factor.is_zero() {
+                return;
+            }
+            for (column, value) in &pivot_entries {
+                row[*column] = &row[*column] - &(&factor * value);
+            }
+        };
+        for (row_index, row) in self.rows.iter_mut().enumerate() {
+            if row_index != pivot_row {
+                eliminate(row);
+            }
+        }
+        if !self.objective.is_empty() {
+            eliminate(&mut self.objective);
+        }
+        self.basis[pivot_row] = pivot_column;
+    }
+
+    // Pivots any artificial variable still in the basis (necessarily at zero)
+    // out for a real column, dropping rows that turn out to be redundant, then
+    // discards the artificial columns altogether.
+    fn remove_artificial_columns(&mut self, column_count: usize) {
+        let mut row_index = 0;
+        while row_index < self.rows.len() {
+            if self.basis[row_index] < column_count {
+                row_index += 1;
+                continue;
+            }
+            match (0..column_count).find(|&column| !self.rows[row_index][column].is_zero()) {
+                Some(column) => {
+                    self.pivot(row_index, column);
+                    row_index += 1;
+                }
+                None => {
+                    self.rows.remove(row_index);
+                    self.basis.remove(row_index);
+                }
+            }
+        }
+        let total_columns = self.column_count();
+        for row in self.rows.iter_mut() {
+            let rhs = row[total_columns].clone();
+            row.truncate(column_count);
+            row.push(rhs);
+        }
+        self.allowed.truncate(column_count);
+        self.objective.clear();
+    }
+
+    // A non-basic column with a positive reduced cost would make the current
+    // objective worse, so it is held at zero for every later objective.
+    fn fix_suboptimal_columns(&mut self) {
+        for column in 0..self.column_count() {
+            if self.objective[column] > BigFraction::zero() {
+                self.allowed[column] = false;
+            }
+        }
+    }
+
+    fn solution(&self, column_count: usize) -> Vec<BigFraction> {
+        let rhs_column = self.column_count();
+        let mut solution = vec![BigFraction::zero(); column_count];
+        for (row_index, &basic_column) in self.basis.iter().enumerate() {
+            solution[basic_column] = self.rows[row_index][rhs_column].clone();
+        }
+        solution
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn big(numerator: u64, denominator: u64) -> BigFraction {
+        BigFraction::new(numerator, denominator)
+    }
+
+    fn matrix(columns: Vec<Vec<(usize, BigFraction)>>, row_count: usize) -> SparseMatrix {
+        let mut matrix = SparseMatrix::new(row_count);
+        for column in columns {
+            matrix.add_column(column);
+        }
+        matrix
+    }
+
+    // x + y = 2 with x costing 1 and y costing 2 only uses x.
+    #[test]
+    fn minimizes_the_cost() {
+        let constraints = matrix(vec![vec![(0, big(1, 1))], vec![(0, big(1, 1))]], 1);
+        let solution =
+            minimize_lexicographic(&constraints, &[big(2, 1)], &[vec![big(1, 1), big(2, 1)]]).unwrap();
+        assert_eq!(solution, vec![big(2, 1), big(0, 1)]);
+    }
+
+    // Both columns tie on the first objective, so the second one decides.
+    #[test]
+    fn breaks_ties_with_later_objectives() {
+        let constraints = matrix(vec![vec![(0, big(1, 1))], vec![(0, big(1, 1))]], 1);
+        let objectives = [vec![big(1, 1), big(1, 1)], vec![big(3, 1), big(1, 1)]];
+        let solution = minimize_lexicographic(&constraints, &[big(1, 2)], &objectives).unwrap();
+        assert_eq!(solution, vec![big(0, 1), big(1, 2)]);
+    }
+
+    #[test]
+    fn reports_infeasible_problems() {
+        let constraints = matrix(vec![vec![(0, -big(1, 1))]], 1);
+        let result = minimize_lexicographic(&constraints, &[big(1, 1)], &[vec![big(1, 1)]]);
+        assert_eq!(result, Err(SolverError::Infeasible));
+    }
+
+    // x - y = 1 lets both grow forever, and y has a negative cost.
+    #[test]
+    fn reports_unbounded_problems() {
+        let constraints = matrix(vec![vec![(0, big(1, 1))], vec![(0, -big(1, 1))]], 1);
+        let result = minimize_lexicographic(&constraints, &[big(1, 1)], &[vec![big(0, 1), -big(1, 1)]]);
+        assert_eq!(result, Err(SolverError::Unbounded));
+    }
+
+    #[test]
+    fn handles_negative_right_hand_sides() {
+        let constraints = matrix(vec![vec![(0, -big(2, 1))]], 1);
+        let solution = minimize_lexicographic(&constraints, &[-big(3, 1)], &[vec![big(1, 1)]]).unwrap();
+        assert_eq!(solution, vec![big(3, 2)]);
+    }
+}
@@ -0,0 +1,65 @@
+use fraction::{BigFraction, Zero};
+
+pub struct SparseMatrix {
+    row_count: usize,
+    columns: Vec<Vec<(usize, BigFraction)>>,
+}
+impl SparseMatrix {
+    pub fn new(row_count: usize) -> Self {
+        SparseMatrix {
+            row_count,
+            columns: Vec::new(),
+        }
+    }
+
+    pub fn add_column(&mut self, entries: Vec<(usize, BigFraction)>) -> usize {
+        let mut column: Vec<(usize, BigFraction)> = Vec::new();
+        for (row, value) in entries {
+            match column.iter_mut().find(|(existing_row, _value)| *existing_row == row) {
+                Some((_row, existing_value)) => *existing_value = &*existing_value + &value,
+                None => column.push((row, value)),
+            }
+        }
+        column.retain(|(_row, value)| !value.is_zero());
+        self.columns.push(column);
+        self.columns.len() - 1
+    }
+
+    pub fn row_count(&self) -> usize {
+        self.row_count
+    }
+
+    pub fn column_count(&self) -> usize {
+        self.columns.len()
+    }
+
+    pub fn to_dense(&self) -> Vec<Vec<BigFraction>> {
+        let mut rows = vec![vec![BigFraction::zero(); self.columns.len()]; self.row_count];
+        for (column_index, column) in self.columns.iter().enumerate() {
+            for (row, value) in column {
+                rows[*row][column_index] = value.clone();
+            }
+        }
+        rows
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn merges_entries_for_the_same_row() {
+        let mut matrix = SparseMatrix::new(2);
+        matrix.add_column(vec![
+            (0, BigFraction::from(2)),
+            (1, BigFraction::from(1)),
+            (0, BigFraction::from(3)),
+            (1, -BigFraction::from(1)),
+        ]);
+        assert_eq!(
+            matrix.to_dense(),
+            vec![vec![BigFraction::from(5)], vec![BigFraction::zero()]]
+        );
+    }
+}
@@ -0,0 +1,194 @@
+use crate::enums::*;
+use crate::materials::material::Material;
+use crate::recipe::Recipe;
+use crate::solver::SolverError;
+use fraction::{CheckedMul, Fraction};
+use std::collections::HashMap;
+
+// The largest multiplier whole numbers may scale a calculation up by.
+const MAX_WHOLE_RATIO: u64 = 1_000_000;
+
+// One item in the breakdown of a target: the machines making it for this
+// branch only, and the ingredients those machines consume.
+#[derive(Clone, Debug)]
+pub struct ProductionNode<'a> {
+    pub material: &'a Material,
+    pub rate: Fraction,
+    pub producers: Vec<(&'a Recipe, Fraction)>,
+    pub children: Vec<ProductionNode<'a>>,
+    pub repeated: bool,
+}
+impl<'a> ProductionNode<'a> {
+    // None when a scaled number is too large to hold.
+    pub fn scaled(&self, ratio: Fraction) -> Option<ProductionNode<'a>> {
+        Some(ProductionNode {
+            material: self.material,
+            rate: self.rate.checked_mul(&ratio)?,
+            producers: self
+                .producers
+                .iter()
+                .map(|&(recipe, machines)| Some((recipe, machines.checked_mul(&ratio)?)))
+                .collect::<Option<Vec<(&Recipe, Fraction)>>>()?,
+            children: self
+                .children
+                .iter()
+                .map(|child| child.scaled(ratio))
+                .collect::<Option<Vec<ProductionNode>>>()?,
+            repeated: self.repeated,
+        })
+    }
+}
+
+pub struct TotalRawResult<'a> {
+    pub producers: HashMap<&'a Recipe, Fraction>,
+    pub total_raw: HashMap<&'a Material, Fraction>,
+    pub surplus: HashMap<&'a Material, Fraction>,
+    pub extractors: HashMap<&'a Material, (Extractor, Fraction)>,
+    pub item_rates: HashMap<&'a Material, Fraction>,
+    pub tree: Vec<ProductionNode<'a>>,
+    pub rounded: bool,
+}
+impl<'a> TotalRawResult<'a> {
+    pub fn new(
+        producers: HashMap<&'a Recipe, Fraction>,
+        total_raw: HashMap<&'a Material, Fraction>,
+        surplus: HashMap<&'a Material, Fraction>,
+    ) -> TotalRawResult<'a> {
+        TotalRawResult {
+            producers,
+            total_raw,
+            surplus,
+            extractors: HashMap::new(),
+            item_rates: HashMap::new(),
+            tree: Vec::new(),
+            rounded: false,
+        }
+    }
+    // Scales everything by the smallest ratio that makes each machine count,
+    // and each raw amount unless ignored, a whole number. Ratios above
+    // MAX_WHOLE_RATIO are refused, since the scaled up factory would be
+    // absurd and its numbers may no longer fit.
+    pub fn whole_ratio(
+        &self,
+        ignore_furnaces: bool,
+        ignore_raw: bool,
+    ) -> Result<(Fraction, TotalRawResult<'a>), SolverError> {
+        let mut denominators: Vec<u64> = self
+            .producers
+            .iter()
+            .filter(|(recipe, _fraction)| {
+                !ignore_furnaces || recipe.producer_type != ProducerType::Furnace
+            })
+            .filter_map(|(_recipe, fraction)| fraction.denom().copied())
+            .collect();
+        if !ignore_raw {
+            denominators.extend(
+                self.total_raw
+                    .values()
+                    .filter_map(|fraction| fraction.denom().copied()),
+            );
+        }
+        let ratio = match lcm_multiple(denominators) {
+            Some(ratio) if ratio <= MAX_WHOLE_RATIO => Fraction::from(ratio),
+            _ => return Err(SolverError::NoWholeRatio),
+        };
+        let scale = |fraction: &Fraction| fraction.checked_mul(&ratio).ok_or(SolverError::NoWholeRatio);
+        let new_producers = self
+            .producers
+            .iter()
+            .map(|(&recipe, fraction)| Ok((recipe, scale(fraction)?)))
+            .collect::<Result<_, SolverError>>()?;
+        let new_raw = self
+            .total_raw
+            .iter()
+            .map(|(&material, fraction)| Ok((material, scale(fraction)?)))
+            .collect::<Result<_, SolverError>>()?;
+        let new_surplus = self
+            .surplus
+            .iter()
+            .map(|(&material, fraction)| Ok((material, scale(fraction)?)))
+            .collect::<Result<_, SolverError>>()?;
+        let mut result = TotalRawResult::new(new_producers, new_raw, new_surplus);
+        result.extractors = self
+            .extractors
+            .iter()
+            .map(|(&material, &(extractor, ref fraction))| Ok((material, (extractor, scale(fraction)?))))
+            .collect::<Result<_, SolverError>>()?;
+        result.item_rates = self
+            .item_rates
+            .iter()
+            .map(|(&material, fraction)| Ok((material, scale(fraction)?)))
+            .collect::<Result<_, SolverError>>()?;
+        result.tree = self
+            .tree
+            .iter()
+            .map(|node| node.scaled(ratio))
+            .collect::<Option<Vec<ProductionNode>>>()
+            .ok_or(SolverError::NoWholeRatio)?;
+        result.rounded = self.rounded;
+        Ok((ratio, result))
+    }
+}
+
+fn lcm_multiple(numbers: Vec<u64>) -> Option<u64> {
+    numbers.iter().try_fold(1, |a, b| lcm(a, *b))
+}
+
+fn lcm(a: u64, b: u64) -> Option<u64> {
+    (a / gcd(a, b)).checked_mul(b)
+}
+
+fn gcd(a: u64, b: u64) -> u64 {
+    match b {
+        0 => a,
+        _ => gcd(b, a % b),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::calculator::{Calculator, CalculatorError};
+    use crate::enums::{AssemblingMachineLevel, FurnaceLevel};
+    use crate::materials::material_database::MaterialDatabase;
+
+    #[test]
+    fn scales_to_whole_machines() {
+        let material_database = MaterialDatabase::built_in().unwrap();
+        let calculation = Calculator::new(&material_database)
+            .items("logistic science pack")
+            .rate(Fraction::from(5))
+            .assembling_machine_level(AssemblingMachineLevel::Three)
+            .furnace_level(FurnaceLevel::Steel)
+            .whole_numbers(true)
+            .calculate()
+            .unwrap();
+        assert_eq!(calculation.ratio, Fraction::from(2));
+        assert!(calculation
+            .producers
+            .iter()
+            .all(|(_recipe, machines)| machines.denom() == Some(&1)));
+    }
+
+    #[test]
+    fn refuses_ratios_too_large_to_hold() {
+        let material_database = MaterialDatabase::built_in().unwrap();
+        let result = Calculator::new(&material_database)
+            .items(
+                "automation science pack 1/7, logistic science pack 1/11, chemical science pack 1/13, \
+                 production science pack 1/17, utility science pack 1/19, space science pack 1/23",
+            )
+            .assembling_machine_level(AssemblingMachineLevel::Two)
+            .furnace_level(FurnaceLevel::Steel)
+            .whole_numbers(true)
+            .calculate();
+        assert_eq!(result.err(), Some(CalculatorError::Unsolvable(SolverError::NoWholeRatio)));
+    }
+
+    #[test]
+    fn finds_least_common_multiples_without_overflowing() {
+        assert_eq!(lcm_multiple(vec![4, 6, 5]), Some(60));
+        assert_eq!(lcm_multiple(Vec::new()), Some(1));
+        assert_eq!(lcm_multiple(vec![u64::MAX, u64::MAX - 1]), None);
+    }
+}
@@ -0,0 +1,236 @@
+use crate::enums::FurnaceLevel;
+use crate::enums::AssemblingMachineLevel;
+use crate::enums::BeltTier;
+use crate::enums::MiningDrillLevel;
+use crate::enums::Module;
+use crate::enums::ProducerType;
+use crate::enums::TimeUnit;
+use crate::materials::material::Material;
+use crate::materials::material_database::MaterialDatabase;
+use crate::modules::{module_slots, ModuleConfiguration, MAX_BEACONS};
+use crate::recipe::Recipe;
+use core::fmt::Error;
+use core::fmt::Formatter;
+use std::collections::HashMap;
+use std::fmt::Display;
+use serde::{Deserialize, Serialize};
+
+// Machine tiers used for a single recipe instead of the user's defaults.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct LevelOverride {
+    pub assembling_machine_level: Option<AssemblingMachineLevel>,
+    pub furnace_level: Option<FurnaceLevel>,
+}
+
+impl Display for LevelOverride {
+    fn fmt(&self, formatter: &mut Formatter) -> Result<(), Error> {
+        let mut levels: Vec<String> = Vec::new();
+        if let Some(assembling_machine_level) = self.assembling_machine_level {
+            levels.push(format!("Assembling Machine {}", assembling_machine_level));
+        }
+        if let Some(furnace_level) = self.furnace_level {
+            levels.push(format!("{} Furnace", furnace_level));
+        }
+        write!(formatter, "{}", levels.join(", "))
+    }
+}
+
+// Settings that can't be calculated with. Names that aren't in the materials
+// aren't checked; they are never matched, so they do no harm.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SettingsError {
+    InvalidPumpjackYield,
+    InvalidTreeDepth,
+    TooManyModules(String, usize),
+    TooManyBeacons(String),
+}
+impl Display for SettingsError {
+    fn fmt(&self, formatter: &mut Formatter) -> Result<(), Error> {
+        match self {
+            SettingsError::InvalidPumpjackYield => {
+                write!(formatter, "The pumpjack yield has to be greater than zero")
+            }
+            SettingsError::InvalidTreeDepth => write!(formatter, "The tree depth has to be greater than zero"),
+            SettingsError::TooManyModules(name, maximum_slots) => {
+                write!(formatter, "At most {} modules fit in *{}*", maximum_slots, name)
+            }
+            SettingsError::TooManyBeacons(name) => {
+                write!(formatter, "At most {} beacons can surround *{}*", MAX_BEACONS, name)
+            }
+        }
+    }
+}
+
+// Bumped whenever the stored format changes in a way that needs migrating.
+pub const USER_SETTINGS_VERSION: u32 = 2;
+
+// Fields missing from stored settings, e.g. ones added since they were saved,
+// fall back to their defaults.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UserSettings {
+    pub version: u32,
+    pub assembling_machine_level: AssemblingMachineLevel,
+    pub furnace_level: FurnaceLevel,
+    pub mining_drill_level: MiningDrillLevel,
+    pub mining_productivity_level: u32,
+    pub pumpjack_yield: u64,
+    pub belt_tier: BeltTier,
+    pub display_unit: TimeUnit,
+    pub tree_depth: u32,
+    pub imports: Vec<String>,
+    pub whole_numbers: bool,
+    pub show_pollution: bool,
+    pub ignore_furnaces_for_ratio: bool,
+    pub ignore_raw_for_ratio: bool,
+    pub module_configurations: HashMap<ProducerType, ModuleConfiguration>,
+    pub module_overrides: HashMap<String, ModuleConfiguration>,
+    pub level_overrides: HashMap<String, LevelOverride>,
+    pub recipe_choices: HashMap<String, String>
+}
+impl Default for UserSettings {
+    fn default() -> Self {
+        UserSettings { 
+            version: USER_SETTINGS_VERSION,
+            assembling_machine_level: AssemblingMachineLevel::One, 
+            furnace_level: FurnaceLevel::Stone,
+            mining_drill_level: MiningDrillLevel::Electric,
+            mining_productivity_level: 0,
+            pumpjack_yield: 100,
+            belt_tier: BeltTier::Yellow,
+            display_unit: TimeUnit::Second,
+            tree_depth: 3,
+            imports: Vec::new(),
+            whole_numbers: false,
+            show_pollution: false,
+            ignore_furnaces_for_ratio: false, 
+            ignore_raw_for_ratio: false,
+            module_configurations: HashMap::new(),
+            module_overrides: HashMap::new(),
+            level_overrides: HashMap::new(),
+            recipe_choices: HashMap::new()
+        }
+    }
+}
+impl UserSettings {
+    pub fn assembling_machine_level_for(&self, recipe: &Recipe) -> AssemblingMachineLevel {
+        self.level_override(recipe)
+            .and_then(|level_override| level_override.assembling_machine_level)
+            .unwrap_or(self.assembling_machine_level)
+    }
+
+    pub fn furnace_level_for(&self, recipe: &Recipe) -> FurnaceLevel {
+        self.level_override(recipe)
+            .and_then(|level_override| level_override.furnace_level)
+            .unwrap_or(self.furnace_level)
+    }
+
+    pub fn level_override(&self, recipe: &Recipe) -> Option<&LevelOverride> {
+        self.level_overrides.get(&recipe.name.to_lowercase())
+    }
+
+    // The recipe picked for a material, keyed by its lowercase name.
+    pub fn recipe_choice(&self, material: &Material) -> Option<&String> {
+        self.recipe_choices.get(&material.name.to_lowercase())
+    }
+
+    pub fn choose_recipe(&mut self, recipe: &Recipe, material_database: &MaterialDatabase) {
+        for (material_index, _amount) in &recipe.products {
+            self.recipe_choices.insert(
+                material_database.material(*material_index).name.to_lowercase(),
+                recipe.name.clone(),
+            );
+        }
+    }
+
+    pub fn is_imported(&self, material: &Material) -> bool {
+        self.imports
+            .iter()
+            .any(|name| name.to_lowercase() == material.name.to_lowercase())
+    }
+
+    pub fn validate(&self, material_database: &MaterialDatabase) -> Result<(), SettingsError> {
+        if self.pumpjack_yield == 0 {
+            return Err(SettingsError::InvalidPumpjackYield);
+        }
+        if self.tree_depth == 0 {
+            return Err(SettingsError::InvalidTreeDepth);
+        }
+        let producer_configurations = self
+            .module_configurations
+            .iter()
+            .map(|(producer_type, configuration)| (producer_type.to_string(), *producer_type, configuration));
+        let recipe_configurations = self.module_overrides.iter().filter_map(|(recipe_name, configuration)| {
+            material_database
+                .find_recipe(recipe_name)
+                .map(|recipe| (recipe.name.clone(), recipe.producer_type, configuration))
+        });
+        for (name, producer_type, configuration) in producer_configurations.chain(recipe_configurations) {
+            // Modules beyond a lower tier's slots are only left out, as the
+            // tier can change without resetting them.
+            let maximum_slots = module_slots(&producer_type, &AssemblingMachineLevel::Three, &FurnaceLevel::Electric);
+            if configuration.modules.len() > maximum_slots {
+                return Err(SettingsError::TooManyModules(name, maximum_slots));
+            }
+            if configuration.beacons > MAX_BEACONS {
+                return Err(SettingsError::TooManyBeacons(name));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn module_configuration(&self, recipe: &Recipe) -> ModuleConfiguration {
+        self.fitted_module_configuration(recipe).0
+    }
+
+    // The modules set for a recipe's machine that fit in it, and those that
+    // had to be left out.
+    pub fn fitted_module_configuration(&self, recipe: &Recipe) -> (ModuleConfiguration, Vec<Module>) {
+        let configuration = self
+            .module_overrides
+            .get(&recipe.name.to_lowercase())
+            .or_else(|| self.module_configurations.get(&recipe.producer_type))
+            .cloned()
+            .unwrap_or_default();
+        let slots = module_slots(
+            &recipe.producer_type,
+            &self.assembling_machine_level_for(recipe),
+            &self.furnace_level_for(recipe),
+        );
+        configuration.fitted_to(slots, recipe.allow_productivity)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_settings_that_cannot_be_calculated() {
+        let material_database = MaterialDatabase::built_in().unwrap();
+        assert_eq!(UserSettings::default().validate(&material_database), Ok(()));
+
+        let settings = UserSettings { pumpjack_yield: 0, ..UserSettings::default() };
+        assert_eq!(settings.validate(&material_database), Err(SettingsError::InvalidPumpjackYield));
+
+        let mut settings = UserSettings::default();
+        settings.module_overrides.insert(
+            "iron gear wheel".to_string(),
+            ModuleConfiguration::new(vec![Module::Speed3; 5], 0),
+        );
+        assert_eq!(
+            settings.validate(&material_database),
+            Err(SettingsError::TooManyModules("Iron Gear Wheel".to_string(), 4))
+        );
+
+        let mut settings = UserSettings::default();
+        settings
+            .module_configurations
+            .insert(ProducerType::Furnace, ModuleConfiguration::new(Vec::new(), MAX_BEACONS + 1));
+        assert_eq!(
+            settings.validate(&material_database),
+            Err(SettingsError::TooManyBeacons("Furnace".to_string()))
+        );
+    }
+}
@@ -1,5 +0,0 @@
-pub mod flags;
-pub mod help_command_handler;
-pub mod recipe_command_handler;
-pub mod user_settings_command_handler;
-pub mod update_settings_command_handler;
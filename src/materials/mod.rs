@@ -1,3 +0,0 @@
-pub mod material_database;
-pub mod material;
-pub mod materials_json_parser;
\ No newline at end of file